@@ -0,0 +1,4 @@
+// Embedded migrations must be rebuilt when the directory changes.
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
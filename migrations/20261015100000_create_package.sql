@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS package (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distname TEXT NOT NULL,
+    master_site TEXT NOT NULL,
+    version TEXT NOT NULL,
+    local_version TEXT,
+    last_check DATETIME NOT NULL
+);
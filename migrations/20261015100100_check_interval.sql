@@ -0,0 +1,2 @@
+-- seconds between checks; NULL means global check interval
+ALTER TABLE package ADD COLUMN check_interval INTEGER;
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS version_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    package_id INTEGER NOT NULL REFERENCES package (id) ON DELETE CASCADE,
+    old_version TEXT NOT NULL,
+    new_version TEXT NOT NULL,
+    observed_at DATETIME NOT NULL
+);
+CREATE INDEX IF NOT EXISTS version_history_package_id ON version_history (package_id);
@@ -0,0 +1,11 @@
+-- backend used to check for new versions; see `Source`
+ALTER TABLE package ADD COLUMN source TEXT NOT NULL DEFAULT 'html';
+
+-- backfill from master site host, as previously sniffed on every check
+UPDATE package SET source = CASE
+    WHEN master_site LIKE '%://pypi.org/%' OR master_site LIKE '%://pypi.python.org/%' THEN 'pypi'
+    WHEN master_site LIKE '%://crates.io/%' THEN 'crates'
+    WHEN master_site LIKE '%://github.com/%' THEN 'github'
+    WHEN master_site LIKE '%://gitlab.com/%' THEN 'gitlab'
+    ELSE 'html'
+END;
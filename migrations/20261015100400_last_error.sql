@@ -0,0 +1,3 @@
+-- failure of the last check; NULL when it succeeded
+ALTER TABLE package ADD COLUMN last_error TEXT;
+ALTER TABLE package ADD COLUMN last_error_at DATETIME;
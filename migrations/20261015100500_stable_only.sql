@@ -0,0 +1,2 @@
+-- ignore pre-release versions when checking
+ALTER TABLE package ADD COLUMN stable_only BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- held packages are not checked and not reported as outdated
+ALTER TABLE package ADD COLUMN held BOOLEAN NOT NULL DEFAULT FALSE;
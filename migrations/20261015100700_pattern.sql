@@ -0,0 +1,2 @@
+-- regular expression with one capture group extracting version from HTML page
+ALTER TABLE package ADD COLUMN pattern TEXT;
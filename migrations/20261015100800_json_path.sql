@@ -0,0 +1,2 @@
+-- path of version field in JSON document, for `json` source
+ALTER TABLE package ADD COLUMN json_path TEXT;
@@ -0,0 +1,5 @@
+-- keep the first package of every name, and tell the others apart by id
+UPDATE package SET distname = distname || '-' || id
+WHERE id NOT IN (SELECT min(id) FROM package GROUP BY distname);
+
+CREATE UNIQUE INDEX package_distname ON package (distname);
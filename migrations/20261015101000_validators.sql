@@ -0,0 +1,3 @@
+-- cache validators of the last response, sent back with conditional requests
+ALTER TABLE package ADD COLUMN etag TEXT;
+ALTER TABLE package ADD COLUMN last_modified TEXT;
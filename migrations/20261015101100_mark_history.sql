@@ -0,0 +1,9 @@
+-- local versions replaced by `mark`, so it can be undone
+CREATE TABLE IF NOT EXISTS mark_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    package_id INTEGER NOT NULL REFERENCES package (id) ON DELETE CASCADE,
+    old_local_version TEXT,
+    new_local_version TEXT NOT NULL,
+    marked_at DATETIME NOT NULL
+);
+CREATE INDEX IF NOT EXISTS mark_history_package_id ON mark_history (package_id);
@@ -0,0 +1,2 @@
+-- look for versions in text of HTML page too, not only in links
+ALTER TABLE package ADD COLUMN scan_text BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- fallback master sites, tried in order when master site fails; one URL per line
+ALTER TABLE package ADD COLUMN mirrors TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,2 @@
+-- look at all GitHub releases, including pre-releases, not only the latest one
+ALTER TABLE package ADD COLUMN all_releases BOOLEAN NOT NULL DEFAULT FALSE;
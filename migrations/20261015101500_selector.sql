@@ -0,0 +1,2 @@
+-- CSS selector of HTML page parts looked at for versions
+ALTER TABLE package ADD COLUMN selector TEXT;
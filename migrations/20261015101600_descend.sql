@@ -0,0 +1,2 @@
+-- look for versions in the highest version subdirectory of HTML directory listing
+ALTER TABLE package ADD COLUMN descend BOOLEAN NOT NULL DEFAULT FALSE;
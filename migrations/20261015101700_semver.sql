@@ -0,0 +1,2 @@
+-- compare versions as semantic versions
+ALTER TABLE package ADD COLUMN semver BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- regular expression of upstream versions to skip
+ALTER TABLE package ADD COLUMN ignore TEXT;
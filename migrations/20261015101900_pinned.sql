@@ -0,0 +1,2 @@
+-- version package is pinned at, compared with local version instead of upstream one
+ALTER TABLE package ADD COLUMN pinned TEXT;
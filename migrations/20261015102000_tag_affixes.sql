@@ -0,0 +1,3 @@
+-- prefix and suffix of release tags, stripped to get versions
+ALTER TABLE package ADD COLUMN tag_prefix TEXT;
+ALTER TABLE package ADD COLUMN tag_suffix TEXT;
@@ -0,0 +1,2 @@
+-- epoch given to versions found upstream, for schemes starting over
+ALTER TABLE package ADD COLUMN epoch INTEGER;
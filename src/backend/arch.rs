@@ -0,0 +1,111 @@
+use super::{Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+
+#[derive(Deserialize)]
+struct ArchSearch {
+    results: Vec<ArchPackage>,
+}
+
+#[derive(Deserialize)]
+struct ArchPackage {
+    pkgver: String,
+}
+
+#[derive(Deserialize)]
+struct AurInfo {
+    results: Vec<AurPackage>,
+}
+
+#[derive(Deserialize)]
+struct AurPackage {
+    /// `[epoch:]pkgver-pkgrel`
+    #[serde(rename = "Version")]
+    version: String,
+}
+
+/// Arch Linux official repositories or AUR.
+pub(super) struct Arch;
+
+impl Backend for Arch {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["archlinux.org", "aur.archlinux.org"]
+    }
+
+    // https://wiki.archlinux.org/title/Official_repositories_web_interface
+    // https://wiki.archlinux.org/title/Aurweb_RPC_interface
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            // package page like `https://archlinux.org/packages/extra/x86_64/git/`
+            let Some(name) = query
+                .url
+                .path_segments()
+                .and_then(|segments| segments.rev().find(|segment| !segment.is_empty()))
+            else {
+                query
+                    .notes
+                    .push(format!("No Arch package in URL for {}", query.distname));
+                return Ok(Found::Nothing);
+            };
+            let http = &query.config.http;
+            let version = if query.url.host_str() == Some("aur.archlinux.org") {
+                let info: AurInfo = get_json(
+                    query.client.get("https://aur.archlinux.org/rpc/").query(&[
+                        ("v", "5"),
+                        ("type", "info"),
+                        ("arg[]", name),
+                    ]),
+                    http,
+                )
+                .await?;
+                info.results
+                    .into_iter()
+                    .next()
+                    .map(|package| arch_pkgver(&package.version).to_string())
+            } else {
+                let search: ArchSearch = get_json(
+                    query
+                        .client
+                        .get("https://archlinux.org/packages/search/json/")
+                        .query(&[("name", name)]),
+                    http,
+                )
+                .await?;
+                search
+                    .results
+                    .into_iter()
+                    .next()
+                    .map(|package| package.pkgver)
+            };
+            match version {
+                Some(version) => return Ok(Found::Version(version)),
+                None => query
+                    .notes
+                    .push(format!("No Arch package {name} for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Upstream version from Arch package version `[epoch:]pkgver-pkgrel`.
+fn arch_pkgver(version: &str) -> &str {
+    let version = version
+        .split_once(':')
+        .map_or(version, |(_, version)| version);
+    version
+        .rsplit_once('-')
+        .map_or(version, |(pkgver, _)| pkgver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arch_pkgver() {
+        assert_eq!(arch_pkgver("12.2.0-1"), "12.2.0");
+        assert_eq!(arch_pkgver("1:2.43.0-1"), "2.43.0");
+        assert_eq!(arch_pkgver("5.2.021"), "5.2.021");
+    }
+}
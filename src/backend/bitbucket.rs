@@ -0,0 +1,80 @@
+use super::{highest_version, repository, Backend, Found, Query};
+use crate::{
+    error::CheckError,
+    http::get_json,
+    version::{is_ignored, Version},
+};
+use futures::future::BoxFuture;
+use std::str::FromStr;
+
+/// Page of Bitbucket API paginated response.
+#[derive(Deserialize)]
+struct BitbucketPage {
+    values: Vec<BitbucketName>,
+}
+
+/// Tag or download of Bitbucket repository.
+#[derive(Deserialize)]
+struct BitbucketName {
+    name: String,
+}
+
+/// Bitbucket repository tags, or downloads.
+pub(super) struct Bitbucket;
+
+impl Backend for Bitbucket {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["bitbucket.org"]
+    }
+
+    // https://developer.atlassian.com/cloud/bitbucket/rest/api-group-refs/#api-repositories-workspace-repo-slug-refs-tags-get
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(repo) = repository(&query.url) else {
+                query.notes.push(format!(
+                    "No Bitbucket repository in URL for {}",
+                    query.distname
+                ));
+                return Ok(Found::Nothing);
+            };
+            let (client, config) = (query.client, query.config);
+            let api = format!("https://api.bitbucket.org/2.0/repositories/{repo}");
+            let pattern = query.regex()?;
+            let tags: BitbucketPage = get_json(
+                client.get(format!("{api}/refs/tags?sort=-target.date&pagelen=100")),
+                &config.http,
+            )
+            .await?;
+            let latest = highest_version(
+                tags.values
+                    .iter()
+                    .filter_map(|tag| query.tag_version(pattern.as_ref(), &tag.name)),
+                &query.filter(),
+            );
+            if let Some(tag) = latest {
+                return Ok(Found::Tag(tag.into()));
+            }
+            // no tags; look at uploaded files
+            let downloads: BitbucketPage = get_json(
+                client.get(format!("{api}/downloads?pagelen=100")),
+                &config.http,
+            )
+            .await?;
+            let filter = query.filter();
+            let latest = downloads
+                .values
+                .iter()
+                .filter(|download| !is_ignored(&download.name, &config.html.ignore))
+                .filter_map(|download| Version::from_str(&download.name).ok())
+                .filter(|version| filter.accepts(version))
+                .max();
+            match latest {
+                Some(version) => return Ok(Found::Version(version.to_string())),
+                None => query
+                    .notes
+                    .push(format!("No tags nor downloads for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
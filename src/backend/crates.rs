@@ -0,0 +1,48 @@
+use super::{Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+
+#[derive(Deserialize)]
+struct CratesIoCrate {
+    #[serde(rename = "crate")]
+    krate: CratesIoCrateInfo,
+}
+
+#[derive(Deserialize)]
+struct CratesIoCrateInfo {
+    max_stable_version: Option<String>,
+}
+
+/// crates.io API.
+pub(super) struct Crates;
+
+impl Backend for Crates {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["crates.io"]
+    }
+
+    // https://crates.io/data-access
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(name) = query.url.path_segments().and_then(Iterator::last) else {
+                return Ok(Found::Nothing);
+            };
+            let crates_io_crate: CratesIoCrate = get_json(
+                query
+                    .client
+                    .get(format!("https://crates.io/api/v1/crates/{name}")),
+                &query.config.http,
+            )
+            .await?;
+            match crates_io_crate.krate.max_stable_version {
+                Some(version) => Ok(Found::Version(version)),
+                None => {
+                    query
+                        .notes
+                        .push(format!("No stable version for {}", query.distname));
+                    Ok(Found::Nothing)
+                }
+            }
+        })
+    }
+}
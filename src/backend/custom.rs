@@ -0,0 +1,86 @@
+use super::{html::Html, json::Json, Backend, Found, Query};
+use crate::{config::CustomSource, error::CheckError};
+use futures::future::BoxFuture;
+use reqwest::Url;
+
+/// Source defined in configuration. Version is taken from JSON document if the source or
+/// package has JSON path, otherwise from HTML page.
+pub struct Custom<'c>(pub &'c CustomSource);
+
+impl Custom<'_> {
+    /// URL of document for package `distname` at `site`.
+    fn expand(&self, distname: &str, site: &str) -> String {
+        self.0
+            .url
+            .replace("{name}", distname)
+            .replace("{site}", site.trim_end_matches('/'))
+    }
+}
+
+impl Backend for Custom<'_> {
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let site = self.expand(query.distname, query.site);
+            let mut document = Query {
+                client: query.client,
+                config: query.config,
+                site: &site,
+                url: Url::parse(&site)?,
+                distname: query.distname,
+                version: query.version,
+                stable_only: query.stable_only,
+                // package settings take precedence over the source ones
+                pattern: query.pattern.or(self.0.pattern.as_deref()),
+                json_path: query.json_path.or(self.0.json_path.as_deref()),
+                scan_text: query.scan_text,
+                all_releases: query.all_releases,
+                selector: query.selector.or(self.0.selector.as_deref()),
+                descend: query.descend,
+                ignore: query.ignore,
+                tag_prefix: query.tag_prefix,
+                tag_suffix: query.tag_suffix,
+                epoch: query.epoch,
+                etag: query.etag.take(),
+                last_modified: query.last_modified.take(),
+                notes: &mut *query.notes,
+            };
+            let found = if document.json_path.is_some() {
+                Json.latest(&mut document).await
+            } else {
+                Html.latest(&mut document).await
+            };
+            query.etag = document.etag;
+            query.last_modified = document.last_modified;
+            found
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let source = CustomSource {
+            url: "https://downloads.example.com/{name}/".into(),
+            pattern: None,
+            json_path: None,
+            selector: None,
+        };
+        assert_eq!(
+            Custom(&source).expand("product", "https://example.com/"),
+            "https://downloads.example.com/product/"
+        );
+        let source = CustomSource {
+            url: "{site}/{name}/latest.json".into(),
+            pattern: None,
+            json_path: Some("version".into()),
+            selector: None,
+        };
+        assert_eq!(
+            Custom(&source).expand("product", "https://example.com/releases/"),
+            "https://example.com/releases/product/latest.json"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use super::{highest_version, Backend, Found, Query};
+use crate::{error::CheckError, http::get_json, version::Version};
+use futures::future::BoxFuture;
+use reqwest::Url;
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+struct DockerHubTags {
+    results: Vec<DockerHubTag>,
+}
+
+#[derive(Deserialize)]
+struct DockerHubTag {
+    name: String,
+}
+
+/// Docker Hub image tags.
+pub(super) struct DockerHub;
+
+impl Backend for DockerHub {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["hub.docker.com"]
+    }
+
+    // https://docs.docker.com/docker-hub/api/latest/#tag/repositories
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(repository) = docker_hub_repository(&query.url) else {
+                query
+                    .notes
+                    .push(format!("No image in URL for {}", query.distname));
+                return Ok(Found::Nothing);
+            };
+            let tags: DockerHubTags = get_json(
+                query.client.get(format!(
+                    "https://hub.docker.com/v2/repositories/{repository}/tags\
+                    ?page_size=100&ordering=last_updated"
+                )),
+                &query.config.http,
+            )
+            .await?;
+            let latest = highest_version(
+                tags.results
+                    .iter()
+                    .map(|tag| tag.name.as_str())
+                    .filter(|tag| is_version_tag(tag)),
+                &query.filter(),
+            );
+            match latest {
+                Some(tag) => return Ok(Found::Tag(tag.into())),
+                None => query
+                    .notes
+                    .push(format!("No version tags for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// `namespace/repository` of Docker Hub image, from its page like
+/// `https://hub.docker.com/r/grafana/grafana`, or `https://hub.docker.com/_/nginx` of
+/// an official image.
+fn docker_hub_repository(url: &Url) -> Option<String> {
+    let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+    match segments.next()? {
+        "_" => Some(format!("library/{}", segments.next()?)),
+        "r" => {
+            let namespace = segments.next()?;
+            Some(format!("{namespace}/{}", segments.next()?))
+        }
+        _ => None,
+    }
+}
+
+/// Tag is a plain version like `1.25.3` or `v2.0.0-rc.1`, not a name like `latest` or
+/// a variant like `1.25-alpine`.
+fn is_version_tag(tag: &str) -> bool {
+    Version::from_str(tag).is_ok_and(|version| version.to_string() == tag.trim_start_matches('v'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docker_hub_repository() {
+        let repository = |url| docker_hub_repository(&Url::parse(url).unwrap());
+        assert_eq!(
+            repository("https://hub.docker.com/_/nginx").as_deref(),
+            Some("library/nginx")
+        );
+        assert_eq!(
+            repository("https://hub.docker.com/r/grafana/grafana/tags").as_deref(),
+            Some("grafana/grafana")
+        );
+        assert_eq!(repository("https://hub.docker.com/u/grafana"), None);
+    }
+
+    #[test]
+    fn test_is_version_tag() {
+        assert!(is_version_tag("1.25.3"));
+        assert!(is_version_tag("v2.0.0-rc.1"));
+        assert!(!is_version_tag("latest"));
+        assert!(!is_version_tag("edge"));
+        assert!(!is_version_tag("1.25-alpine"));
+        assert!(!is_version_tag("sha256-abc"));
+    }
+}
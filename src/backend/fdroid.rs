@@ -0,0 +1,72 @@
+use super::{highest_version, is_prerelease, Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FDroidApp {
+    suggested_version_code: u64,
+    packages: Vec<FDroidPackage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FDroidPackage {
+    version_name: String,
+    version_code: u64,
+}
+
+/// F-Droid suggested version of Android app.
+pub(super) struct FDroid;
+
+impl Backend for FDroid {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["f-droid.org"]
+    }
+
+    // https://f-droid.org/en/docs/All_our_APIs/
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            // app page like `https://f-droid.org/en/packages/org.fdroid.fdroid/`
+            let Some(app_id) = query.url.path_segments().and_then(|mut segments| {
+                segments.find(|segment| *segment == "packages")?;
+                segments.next().filter(|app_id| !app_id.is_empty())
+            }) else {
+                query
+                    .notes
+                    .push(format!("No F-Droid app in URL for {}", query.distname));
+                return Ok(Found::Nothing);
+            };
+            let app: FDroidApp = get_json(
+                query
+                    .client
+                    .get(format!("https://f-droid.org/api/v1/packages/{app_id}")),
+                &query.config.http,
+            )
+            .await?;
+            // newer builds may exist, but are not recommended yet
+            let suggested = app
+                .packages
+                .iter()
+                .find(|package| package.version_code == app.suggested_version_code)
+                .map(|package| package.version_name.as_str());
+            let latest = suggested
+                .filter(|version| !(query.stable_only && is_prerelease(version)))
+                .or_else(|| {
+                    highest_version(
+                        app.packages
+                            .iter()
+                            .map(|package| package.version_name.as_str()),
+                        &query.filter(),
+                    )
+                });
+            match latest {
+                Some(version) => return Ok(Found::Tag(version.into())),
+                None => query
+                    .notes
+                    .push(format!("No version for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
@@ -0,0 +1,121 @@
+use super::{Backend, Found, Query};
+use crate::{
+    error::CheckError,
+    version::{Version, VersionFilter},
+};
+use futures::future::BoxFuture;
+use regex::Regex;
+use reqwest::Url;
+use std::str::FromStr;
+
+/// Versions in entry titles of Atom or RSS feed, like GitHub
+/// `https://github.com/owner/repo/releases.atom`.
+pub(super) struct Feed;
+
+impl Backend for Feed {
+    fn detect(&self, url: &Url) -> bool {
+        let path = url.path();
+        path.ends_with(".atom") || path.ends_with(".rss")
+    }
+
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let request = query.client.get(query.site);
+            let Some(body) = query.get_modified(request).await? else {
+                return Ok(Found::NotModified);
+            };
+            let pattern = query.regex()?;
+            match feed_version(&body, pattern.as_ref(), &query.filter()) {
+                Some(version) => return Ok(Found::Version(version.to_string())),
+                None => query
+                    .notes
+                    .push(format!("No version in feed for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Titles of Atom entries or RSS items, without the title of feed itself.
+fn feed_titles(feed: &str) -> Vec<&str> {
+    let entry =
+        Regex::new(r"(?s)<(?:entry|item)[\s>].*?<title[^>]*>(.*?)</title>").expect("valid regex");
+    entry
+        .captures_iter(feed)
+        .filter_map(|captures| captures.get(1))
+        .map(|title| {
+            let title = title.as_str().trim();
+            title
+                .strip_prefix("<![CDATA[")
+                .and_then(|title| title.strip_suffix("]]>"))
+                .unwrap_or(title)
+        })
+        .collect()
+}
+
+/// Highest version in entry titles of `feed`, accepted by `filter`. With `pattern`, version is
+/// its first capture group; titles not matching it are skipped.
+fn feed_version(feed: &str, pattern: Option<&Regex>, filter: &VersionFilter) -> Option<Version> {
+    feed_titles(feed)
+        .into_iter()
+        .filter_map(|title| match pattern {
+            Some(pattern) => Some(pattern.captures(title)?.get(1)?.as_str()),
+            None => Some(title),
+        })
+        .filter_map(|version| Version::from_str(version).ok())
+        .filter(|version| filter.accepts(version))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_version() {
+        let atom = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Release notes from project 2</title>
+  <entry>
+    <id>tag:github.com,2008:Repository/1/v2.1.0-rc1</id>
+    <title>v2.1.0-rc1</title>
+  </entry>
+  <entry>
+    <title type="html">Release 2.0.3</title>
+  </entry>
+  <entry>
+    <title>v2.0.2</title>
+  </entry>
+</feed>"#;
+        let stable_only = VersionFilter {
+            stable_only: true,
+            ..Default::default()
+        };
+        assert_eq!(feed_titles(atom), ["v2.1.0-rc1", "Release 2.0.3", "v2.0.2"]);
+        assert_eq!(
+            feed_version(atom, None, &stable_only),
+            Some(Version::new(vec![2, 0, 3]))
+        );
+        assert_eq!(
+            feed_version(atom, None, &VersionFilter::default()).map(|v| v.to_string()),
+            Some("2.1.0-rc1".into())
+        );
+        let pattern = Regex::new(r"^v(.+)$").unwrap();
+        assert_eq!(
+            feed_version(atom, Some(&pattern), &stable_only),
+            Some(Version::new(vec![2, 0, 2]))
+        );
+
+        let rss = r#"<rss version="2.0"><channel>
+<title>Project blog</title>
+<item><title><![CDATA[Project 5.4 released]]></title></item>
+<item><title><![CDATA[Call for papers 2024]]></title></item>
+</channel></rss>"#;
+        let pattern = Regex::new(r"Project ([\d.]+) released").unwrap();
+        assert_eq!(
+            feed_version(rss, Some(&pattern), &VersionFilter::default()),
+            Some(Version::new(vec![5, 4]))
+        );
+        assert_eq!(feed_version("<rss/>", None, &stable_only), None);
+    }
+}
@@ -0,0 +1,290 @@
+use super::{Backend, Found, Query};
+use crate::{
+    error::CheckError,
+    version::{is_ignored, Version, VersionFilter},
+};
+use futures::future::BoxFuture;
+use regex::Regex;
+use reqwest::Url;
+use std::{io, str::FromStr, time::Duration};
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    time::timeout,
+};
+
+/// File names in directory on FTP server, like `ftp://ftp.gnu.org/gnu/bash/`.
+pub(super) struct Ftp;
+
+impl Backend for Ftp {
+    fn detect(&self, url: &Url) -> bool {
+        url.scheme() == "ftp"
+    }
+
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let limit = Duration::from_secs(query.config.http.timeout);
+            let names = timeout(limit, list_directory(&query.url))
+                .await
+                .map_err(|_| CheckError::Ftp("timed out".into()))?
+                .map_err(|err| CheckError::Ftp(err.to_string()))?;
+            let pattern = query.regex()?;
+            let latest = ftp_version(
+                names.iter().map(String::as_str),
+                pattern.as_ref(),
+                &query.filter(),
+                &query.config.html.ignore,
+            );
+            match latest {
+                Some(version) => return Ok(Found::Version(version.to_string())),
+                None => query
+                    .notes
+                    .push(format!("No version for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Highest version among file names, accepted by `filter`. With `pattern`, version is its
+/// first capture group; otherwise names are looked at like links on HTML page, and ones with
+/// `ignored` suffixes are skipped.
+fn ftp_version<'a>(
+    names: impl Iterator<Item = &'a str>,
+    pattern: Option<&Regex>,
+    filter: &VersionFilter,
+    ignored: &[String],
+) -> Option<Version> {
+    names
+        .filter_map(|name| match pattern {
+            Some(pattern) => Some(pattern.captures(name)?.get(1)?.as_str()),
+            None => Some(name).filter(|name| !is_ignored(name, ignored)),
+        })
+        .filter_map(|version| Version::from_str(version).ok())
+        .filter(|version| filter.accepts(version))
+        .max()
+}
+
+/// Names of files in directory at `url`, listed in passive mode. Login is anonymous, unless
+/// URL carries user name and password.
+async fn list_directory(url: &Url) -> io::Result<Vec<String>> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no host in URL"))?;
+    let control = TcpStream::connect((host, url.port().unwrap_or(21))).await?;
+    let peer = control.peer_addr()?.ip();
+    let (reader, mut writer) = control.into_split();
+    let mut reader = BufReader::new(reader);
+    expect(read_reply(&mut reader).await?, &[220])?;
+
+    let user = match url.username() {
+        "" => "anonymous",
+        user => user,
+    };
+    writer
+        .write_all(format!("USER {user}\r\n").as_bytes())
+        .await?;
+    let reply = read_reply(&mut reader).await?;
+    if reply.0 == 331 {
+        let password = url.password().unwrap_or("anonymous@");
+        writer
+            .write_all(format!("PASS {password}\r\n").as_bytes())
+            .await?;
+        expect(read_reply(&mut reader).await?, &[230, 202])?;
+    } else {
+        expect(reply, &[230])?;
+    }
+    writer
+        .write_all(format!("CWD {}\r\n", url.path()).as_bytes())
+        .await?;
+    expect(read_reply(&mut reader).await?, &[250])?;
+
+    // data connection goes to the same host, as address in PASV reply may be private
+    writer.write_all(b"EPSV\r\n").await?;
+    let mut reply = read_reply(&mut reader).await?;
+    if reply.0 != 229 {
+        writer.write_all(b"PASV\r\n").await?;
+        reply = read_reply(&mut reader).await?;
+    }
+    let port = passive_port(&reply).ok_or_else(|| reply_error(&reply))?;
+    let mut data = TcpStream::connect((peer, port)).await?;
+    writer.write_all(b"NLST\r\n").await?;
+    expect(read_reply(&mut reader).await?, &[125, 150])?;
+    let mut listing = String::new();
+    data.read_to_string(&mut listing).await?;
+    drop(data);
+    expect(read_reply(&mut reader).await?, &[226, 250])?;
+    writer.write_all(b"QUIT\r\n").await?;
+
+    Ok(listing
+        .lines()
+        // some servers list paths
+        .filter_map(|line| line.trim().rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .map(Into::into)
+        .collect())
+}
+
+/// Reply of FTP server: code, and text of the last line.
+type Reply = (u16, String);
+
+/// Read reply, which may span several lines, like `220-Welcome` ... `220 Ready`.
+async fn read_reply<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<Reply> {
+    let mut line = String::new();
+    let mut code = None;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let first = line.get(..3).and_then(|code| u16::from_str(code).ok());
+        let code = *code.get_or_insert(first.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("bad reply {line:?}"))
+        })?);
+        // the last line has code followed by space
+        if first == Some(code) && line.as_bytes().get(3) != Some(&b'-') {
+            return Ok((code, line.get(4..).unwrap_or_default().trim().into()));
+        }
+    }
+}
+
+/// Fail unless reply has one of expected codes.
+fn expect(reply: Reply, codes: &[u16]) -> io::Result<()> {
+    if codes.contains(&reply.0) {
+        Ok(())
+    } else {
+        Err(reply_error(&reply))
+    }
+}
+
+/// Unexpected reply, as error.
+fn reply_error((code, text): &Reply) -> io::Error {
+    io::Error::other(format!("{code} {text}"))
+}
+
+/// Data port from reply to EPSV, like `Entering Extended Passive Mode (|||6446|)`, or to PASV,
+/// like `Entering Passive Mode (192,168,1,2,25,46)`.
+fn passive_port((code, text): &Reply) -> Option<u16> {
+    let (_, rest) = text.split_once('(')?;
+    let (numbers, _) = rest.split_once(')')?;
+    match code {
+        229 => numbers.trim_matches('|').parse().ok(),
+        227 => {
+            // octets from server, which may be out of range
+            let mut numbers = numbers.rsplit(',').map(|n| u8::from_str(n.trim()));
+            let low = numbers.next()?.ok()?;
+            let high = numbers.next()?.ok()?;
+            Some(u16::from(high) * 256 + u16::from(low))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passive_port() {
+        let reply = |code, text: &str| (code, text.to_string());
+        assert_eq!(
+            passive_port(&reply(229, "Entering Extended Passive Mode (|||6446|)")),
+            Some(6446)
+        );
+        assert_eq!(
+            passive_port(&reply(227, "Entering Passive Mode (192,168,1,2,25,46).")),
+            Some(25 * 256 + 46)
+        );
+        assert_eq!(
+            passive_port(&reply(227, "Entering Passive Mode (192,168,1,2,300,46).")),
+            None
+        );
+        assert_eq!(passive_port(&reply(500, "EPSV not understood")), None);
+    }
+
+    #[tokio::test]
+    async fn test_read_reply() {
+        let mut input: &[u8] = b"220-Welcome\r\n220-to FTP\r\n220 Ready\r\n230 Logged in\r\n";
+        assert_eq!(read_reply(&mut input).await.unwrap(), (220, "Ready".into()));
+        assert_eq!(
+            read_reply(&mut input).await.unwrap(),
+            (230, "Logged in".into())
+        );
+        assert!(read_reply(&mut input).await.is_err());
+    }
+
+    #[test]
+    fn test_ftp_version() {
+        let names = [
+            "bash-5.2.tar.gz",
+            "bash-5.2.tar.gz.sig",
+            "bash-5.3-rc1.tar.gz",
+            "bash-5.1.16.tar.gz",
+            "README",
+        ];
+        let ignored = crate::config::HtmlConfig::default().ignore;
+        let stable_only = VersionFilter {
+            stable_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            ftp_version(names.into_iter(), None, &stable_only, &ignored),
+            Some(Version::new(vec![5, 2]))
+        );
+        let pattern = Regex::new(r"^bash-([\d.]+)\.tar").unwrap();
+        assert_eq!(
+            ftp_version(
+                names.into_iter(),
+                Some(&pattern),
+                &VersionFilter::default(),
+                &ignored
+            ),
+            Some(Version::new(vec![5, 2]))
+        );
+    }
+
+    /// Scripted FTP server listing `names`.
+    fn serve_listing(names: &'static str) -> (String, std::thread::JoinHandle<()>) {
+        use std::io::{BufRead, Write};
+
+        let control = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let data = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ftp://{}/gnu/bash/", control.local_addr().unwrap());
+        let data_port = data.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = control.accept().unwrap();
+            let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            writer.write_all(b"220-Welcome\r\n220 Ready\r\n").unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 {
+                let reply = match line.split_whitespace().next().unwrap() {
+                    "USER" => "331 Password required".into(),
+                    "PASS" => "230 Logged in".into(),
+                    "CWD" if line.trim_end() == "CWD /gnu/bash/" => "250 OK".into(),
+                    "EPSV" => format!("229 Entering Extended Passive Mode (|||{data_port}|)"),
+                    "NLST" => {
+                        writer.write_all(b"150 Here it comes\r\n").unwrap();
+                        let (mut stream, _) = data.accept().unwrap();
+                        stream.write_all(names.as_bytes()).unwrap();
+                        drop(stream);
+                        "226 Done".into()
+                    }
+                    "QUIT" => break,
+                    _ => "500 Unknown".into(),
+                };
+                writer.write_all(format!("{reply}\r\n").as_bytes()).unwrap();
+                line.clear();
+            }
+        });
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn test_list_directory() {
+        let (url, server) = serve_listing("bash-5.1.tar.gz\r\n/gnu/bash/bash-5.2.tar.gz\r\n");
+        let names = list_directory(&Url::parse(&url).unwrap()).await.unwrap();
+        server.join().unwrap();
+        assert_eq!(names, ["bash-5.1.tar.gz", "bash-5.2.tar.gz"]);
+    }
+}
@@ -0,0 +1,123 @@
+use super::{highest_version, repository, Backend, Found, Query};
+use crate::{error::CheckError, http::get_text};
+use futures::future::BoxFuture;
+use reqwest::Url;
+
+/// Tags of any git repository served over smart HTTP, with master site like
+/// `git+https://example.com/project.git`.
+pub(super) struct Git;
+
+impl Backend for Git {
+    fn detect(&self, url: &Url) -> bool {
+        url.scheme().starts_with("git+")
+    }
+
+    // https://git-scm.com/docs/http-protocol#_smart_clients
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let repo = query.url.as_str();
+            let repo = repo.strip_prefix("git+").unwrap_or(repo);
+            let repo = repo.trim_end_matches('/').to_string();
+            latest_tag(query, &repo).await
+        })
+    }
+}
+
+/// Tags of sourcehut git repository, like `https://git.sr.ht/~user/project`.
+pub(super) struct Sourcehut;
+
+impl Backend for Sourcehut {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["git.sr.ht"]
+    }
+
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            // any page of repository, like `https://git.sr.ht/~user/project/refs`
+            let Some(repo) = repository(&query.url) else {
+                query.notes.push(format!(
+                    "No sourcehut repository in URL for {}",
+                    query.distname
+                ));
+                return Ok(Found::Nothing);
+            };
+            latest_tag(query, &format!("https://git.sr.ht/{repo}")).await
+        })
+    }
+}
+
+/// Highest tag of git repository at `repo`, taken from its references advertisement.
+async fn latest_tag(query: &mut Query<'_>, repo: &str) -> Result<Found, CheckError> {
+    let refs = get_text(
+        query
+            .client
+            .get(format!("{repo}/info/refs?service=git-upload-pack")),
+        &query.config.http,
+    )
+    .await?;
+    let pattern = query.regex()?;
+    let latest = highest_version(
+        git_tags(&refs)
+            .into_iter()
+            .filter_map(|tag| query.tag_version(pattern.as_ref(), tag)),
+        &query.filter(),
+    );
+    match latest {
+        Some(tag) => return Ok(Found::Tag(tag.into())),
+        None => query.notes.push(format!("No tags for {}", query.distname)),
+    }
+    Ok(Found::Nothing)
+}
+
+/// Tag names advertised by git smart HTTP server, in pkt-line format: four hexadecimal digits
+/// of line length, then object ID and reference name. Peeled tags (`^{}`) are listed once.
+fn git_tags(advertisement: &str) -> Vec<&str> {
+    let mut tags = Vec::new();
+    let mut rest = advertisement;
+    while let Some(length) = rest
+        .get(..4)
+        .and_then(|length| usize::from_str_radix(length, 16).ok())
+    {
+        // flush packet
+        if length == 0 {
+            rest = &rest[4..];
+            continue;
+        }
+        let Some(line) = rest.get(4..length) else {
+            break;
+        };
+        rest = &rest[length..];
+        // capabilities follow the first reference after NUL
+        let line = line.split('\0').next().unwrap_or_default().trim_end();
+        let Some(tag) = line
+            .split_once(' ')
+            .and_then(|(_, name)| name.strip_prefix("refs/tags/"))
+        else {
+            continue;
+        };
+        let tag = tag.strip_suffix("^{}").unwrap_or(tag);
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_tags() {
+        let advertisement = "001e# service=git-upload-pack\n\
+            0000\
+            004aa4ff4b2c7b5bbd7e8d79a2b9d3df0ad1d5b5f6c1 HEAD\0multi_ack side-band-64k\n\
+            003da4ff4b2c7b5bbd7e8d79a2b9d3df0ad1d5b5f6c1 refs/heads/main\n\
+            003c0d6b4e3c8a1f2e5b7c9d0a1b2c3d4e5f6a7b8c9d refs/tags/v1.0\n\
+            003fa4ff4b2c7b5bbd7e8d79a2b9d3df0ad1d5b5f6c1 refs/tags/v1.0^{}\n\
+            003c1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f refs/tags/v1.1\n\
+            0000";
+        assert_eq!(git_tags(advertisement), ["v1.0", "v1.1"]);
+        assert!(git_tags("not a git repository").is_empty());
+    }
+}
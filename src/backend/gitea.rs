@@ -0,0 +1,75 @@
+use super::{
+    github::{GitHubReleaseInfo, GitHubTag},
+    highest_version, is_instance, is_prerelease, repository, Backend, Found, Query,
+};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+use reqwest::{header, StatusCode};
+
+/// Gitea or Forgejo releases, or tags.
+pub(super) struct Gitea;
+
+impl Backend for Gitea {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["codeberg.org"]
+    }
+
+    // https://gitea.com/api/swagger#/repository/repoGetLatestRelease
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(repo) = repository(&query.url) else {
+                query
+                    .notes
+                    .push(format!("No repository in URL for {}", query.distname));
+                return Ok(Found::Nothing);
+            };
+            let (client, config) = (query.client, query.config);
+            let pattern = query.regex()?;
+            // token not sent to any host the package happens to point at
+            let token = config
+                .gitea
+                .token
+                .as_ref()
+                .filter(|_| is_instance(&query.url, self.hosts(), &config.gitea.hosts));
+            let api = format!(
+                "{}/api/v1/repos/{repo}",
+                query.url.origin().ascii_serialization()
+            );
+            let gitea_get = |url: String| {
+                let request = client.get(url);
+                match token {
+                    Some(token) => request.header(header::AUTHORIZATION, format!("token {token}")),
+                    None => request,
+                }
+            };
+            match get_json::<GitHubReleaseInfo>(
+                gitea_get(format!("{api}/releases/latest")),
+                &config.http,
+            )
+            .await
+            {
+                Ok(release) => match query.tag_version(pattern.as_ref(), &release.tag_name) {
+                    // not following tag scheme of package; fall back to tags
+                    None => (),
+                    Some(tag) if query.stable_only && is_prerelease(tag) => (),
+                    Some(tag) => return Ok(Found::Tag(tag.into())),
+                },
+                // no release yet; fall back to tags
+                Err(CheckError::Status(StatusCode::NOT_FOUND)) => (),
+                Err(err) => return Err(err),
+            }
+            let tags: Vec<GitHubTag> =
+                get_json(gitea_get(format!("{api}/tags")), &config.http).await?;
+            let latest = highest_version(
+                tags.iter()
+                    .filter_map(|tag| query.tag_version(pattern.as_ref(), &tag.name)),
+                &query.filter(),
+            );
+            match latest {
+                Some(tag) => return Ok(Found::Tag(tag.into())),
+                None => query.notes.push(format!("No tags for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
@@ -0,0 +1,162 @@
+use super::{highest_version, is_prerelease, repository, Backend, Found, Query};
+use crate::{
+    config::{Config, GitHubConfig},
+    error::CheckError,
+    http::{get_json, unix_now},
+};
+use futures::future::BoxFuture;
+use reqwest::{header, Client, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// When exhausted GitHub API rate limit resets, in seconds since Unix epoch.
+/// Shared by all concurrent checks, so they stop hitting the API together.
+static GITHUB_RATE_LIMIT_RESET: AtomicU64 = AtomicU64::new(0);
+
+/// Release, as returned by GitHub and Gitea API.
+#[derive(Deserialize)]
+pub(super) struct GitHubReleaseInfo {
+    pub(super) tag_name: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    draft: bool,
+    prerelease: bool,
+}
+
+/// Tag, as returned by GitHub and Gitea API.
+#[derive(Deserialize)]
+pub(super) struct GitHubTag {
+    pub(super) name: String,
+}
+
+/// GitHub releases, or tags.
+pub(super) struct GitHub;
+
+impl Backend for GitHub {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["github.com"]
+    }
+
+    // https://docs.github.com/en/rest/releases/releases#get-the-latest-release
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(repo) = repository(&query.url) else {
+                query.notes.push(format!(
+                    "No GitHub repository in URL for {}",
+                    query.distname
+                ));
+                return Ok(Found::Nothing);
+            };
+            let (client, config) = (query.client, query.config);
+            let api = format!("https://api.github.com/repos/{repo}");
+            let pattern = query.regex()?;
+            if config.github.prefer_tags {
+                // look among tags only
+            } else if query.all_releases {
+                // https://docs.github.com/en/rest/releases/releases#list-releases
+                let path = format!("{api}/releases?per_page=100");
+                let releases: Vec<GitHubRelease> = github_json(client, path, config).await?;
+                // releases are sorted by creation date, newest first; drafts are listed
+                // only with push access
+                let latest = releases
+                    .iter()
+                    .filter(|release| {
+                        !release.draft
+                            && !(query.stable_only
+                                && (release.prerelease || is_prerelease(&release.tag_name)))
+                    })
+                    .find_map(|release| query.tag_version(pattern.as_ref(), &release.tag_name));
+                if let Some(version) = latest {
+                    return Ok(Found::Tag(version.into()));
+                }
+            } else {
+                let path = format!("{api}/releases/latest");
+                match github_json::<GitHubReleaseInfo>(client, path, config).await {
+                    // pre-release tag on a regular release; look among tags
+                    Ok(github_info)
+                        if query.stable_only && is_prerelease(&github_info.tag_name) => {}
+                    Ok(github_info) => {
+                        if let Some(version) =
+                            query.tag_version(pattern.as_ref(), &github_info.tag_name)
+                        {
+                            return Ok(Found::Tag(version.into()));
+                        }
+                    }
+                    // no release yet; fall back to tags
+                    Err(CheckError::Status(StatusCode::NOT_FOUND)) => (),
+                    Err(err) => return Err(err),
+                }
+            }
+            // https://docs.github.com/en/rest/repos/repos#list-repository-tags
+            let path = format!("{api}/tags");
+            let tags: Vec<GitHubTag> = github_json(client, path, config).await?;
+            let latest = highest_version(
+                tags.iter()
+                    .filter_map(|tag| query.tag_version(pattern.as_ref(), &tag.name)),
+                &query.filter(),
+            );
+            match latest {
+                Some(tag) => return Ok(Found::Tag(tag.into())),
+                None => query.notes.push(format!("No tags for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Build GitHub API request with optional authentication.
+fn github_get(client: &Client, url: String, config: &GitHubConfig) -> RequestBuilder {
+    let request = client
+        .get(url)
+        .header(header::ACCEPT, "application/vnd.github+json")
+        .header("X-GitHub-Api-Version", "2022-11-28");
+    match &config.token {
+        // fine-grained or classic personal access token
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
+
+/// Fetch JSON from GitHub API. When the rate limit is exhausted, wait for reset if it is
+/// near enough, otherwise fail immediately.
+async fn github_json<T: DeserializeOwned>(
+    client: &Client,
+    url: String,
+    config: &Config,
+) -> Result<T, CheckError> {
+    let hint = config
+        .github
+        .token
+        .is_none()
+        .then_some("set github.token in configuration to raise the limit");
+    loop {
+        let reset = GITHUB_RATE_LIMIT_RESET.load(Ordering::Relaxed);
+        let now = unix_now();
+        if reset > now {
+            if reset - now > config.github.rate_limit_wait {
+                return Err(CheckError::RateLimited(reset, hint));
+            }
+            tokio::time::sleep(Duration::from_secs(reset - now)).await;
+        }
+        match get_json(
+            github_get(client, url.clone(), &config.github),
+            &config.http,
+        )
+        .await
+        {
+            Err(CheckError::RateLimited(reset, _)) if reset > unix_now() => {
+                GITHUB_RATE_LIMIT_RESET.fetch_max(reset, Ordering::Relaxed);
+            }
+            Err(CheckError::RateLimited(reset, _)) => {
+                return Err(CheckError::RateLimited(reset, hint));
+            }
+            result => return result,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use super::{is_instance, is_prerelease, Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+
+#[derive(Deserialize)]
+struct GitLabRelease {
+    tag_name: String,
+}
+
+/// GitLab releases.
+pub(super) struct GitLab;
+
+impl Backend for GitLab {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["gitlab.com"]
+    }
+
+    // https://docs.gitlab.com/ee/api/releases/#list-releases
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let project = query.url.path().trim_matches('/').replace('/', "%2F");
+            let pattern = query.regex()?;
+            // gitlab.com or self-hosted instance
+            let mut request = query.client.get(format!(
+                "{}/api/v4/projects/{project}/releases",
+                query.url.origin().ascii_serialization()
+            ));
+            if let Some(token) = &query.config.gitlab.token {
+                // not to any host the package happens to point at
+                if is_instance(&query.url, self.hosts(), &query.config.gitlab.hosts) {
+                    request = request.header("PRIVATE-TOKEN", token);
+                }
+            }
+            // releases are sorted by release date, newest first
+            let releases: Vec<GitLabRelease> = get_json(request, &query.config.http).await?;
+            let latest = releases
+                .iter()
+                .filter_map(|release| query.tag_version(pattern.as_ref(), &release.tag_name))
+                .find(|tag| !(query.stable_only && is_prerelease(tag)));
+            match latest {
+                Some(tag) => return Ok(Found::Tag(tag.into())),
+                None => query
+                    .notes
+                    .push(format!("No releases for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
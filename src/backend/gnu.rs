@@ -0,0 +1,112 @@
+use super::{Backend, Found, Query};
+use crate::{
+    error::CheckError,
+    version::{is_ignored, Version, VersionFilter},
+};
+use futures::future::BoxFuture;
+use regex::Regex;
+use reqwest::Url;
+use std::str::FromStr;
+
+/// Release files and directories of a package on GNU or Savannah download site.
+pub(super) struct Gnu;
+
+impl Backend for Gnu {
+    fn hosts(&self) -> &'static [&'static str] {
+        &[
+            "ftp.gnu.org",
+            "ftpmirror.gnu.org",
+            "download.savannah.gnu.org",
+        ]
+    }
+
+    // https://www.gnu.org/prep/ftp.html
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(name) = gnu_package(&query.url).map(String::from) else {
+                query
+                    .notes
+                    .push(format!("No GNU package in URL for {}", query.distname));
+                return Ok(Found::Nothing);
+            };
+            let request = query.client.get(query.site);
+            let Some(listing) = query.get_modified(request).await? else {
+                return Ok(Found::NotModified);
+            };
+            let latest = gnu_version(&listing, &name, &query.filter(), &query.config.html.ignore);
+            match latest {
+                Some(version) => return Ok(Found::Version(version.to_string())),
+                None => query
+                    .notes
+                    .push(format!("No {name} releases for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Package directory name from GNU download site URL, like `https://ftp.gnu.org/gnu/bash/`,
+/// or Savannah one, like `https://download.savannah.gnu.org/releases/acl/`.
+fn gnu_package(url: &Url) -> Option<&str> {
+    let mut segments = url.path_segments()?;
+    match segments.next()? {
+        "gnu" | "releases" => segments.next().filter(|name| !name.is_empty()),
+        _ => None,
+    }
+}
+
+/// Highest version in GNU directory listing of package `name`: release files like
+/// `bash-5.2.tar.gz` and version directories like `gcc-13.2.0/`. Signatures and other
+/// files with `ignored` suffixes, `latest` links and files of other packages are skipped.
+fn gnu_version(
+    listing: &str,
+    name: &str,
+    filter: &VersionFilter,
+    ignored: &[String],
+) -> Option<Version> {
+    let href = Regex::new(r#"href="([^"?#]+)""#).expect("valid regex");
+    let prefix = format!("{name}-");
+    href.captures_iter(listing)
+        .filter_map(|captures| captures.get(1))
+        .filter_map(|href| href.as_str().trim_end_matches('/').rsplit('/').next())
+        .filter(|file| !is_ignored(file, ignored) && !file.contains("latest"))
+        // version taken after package name, which may have digits, like `m4`
+        .filter_map(|file| Version::from_str(file.strip_prefix(&prefix)?).ok())
+        .filter(|version| filter.accepts(version))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gnu_version() {
+        let listing = r#"<a href="?C=N;O=D">Name</a>
+<a href="m4-1.4.18.tar.gz">m4-1.4.18.tar.gz</a>
+<a href="m4-1.4.19.tar.xz">m4-1.4.19.tar.xz</a>
+<a href="m4-1.4.20.tar.xz.sig">m4-1.4.20.tar.xz.sig</a>
+<a href="m4-latest.tar.xz">m4-latest.tar.xz</a>
+<a href="m4-2.0.0/">m4-2.0.0/</a>
+<a href="gm4-3.0.tar.gz">gm4-3.0.tar.gz</a>
+<a href="/gnu/">Parent Directory</a>"#;
+        let ignored = crate::config::HtmlConfig::default().ignore;
+        let filter = VersionFilter::default();
+        assert_eq!(
+            gnu_version(listing, "m4", &filter, &ignored),
+            Some(Version::new(vec![2, 0, 0]))
+        );
+        assert_eq!(gnu_version(listing, "bash", &filter, &ignored), None);
+
+        let package = |url| gnu_package(&Url::parse(url).unwrap()).map(String::from);
+        assert_eq!(
+            package("https://ftp.gnu.org/gnu/m4/").as_deref(),
+            Some("m4")
+        );
+        assert_eq!(
+            package("https://download.savannah.gnu.org/releases/acl/").as_deref(),
+            Some("acl")
+        );
+        assert_eq!(package("https://ftp.gnu.org/pub/"), None);
+    }
+}
@@ -0,0 +1,88 @@
+use super::{highest_version, Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+use reqwest::{header, Url};
+
+#[derive(Deserialize)]
+struct HackagePreferred {
+    #[serde(rename = "normal-version", default)]
+    normal_version: Vec<String>,
+}
+
+/// Hackage preferred versions.
+pub(super) struct Hackage;
+
+impl Backend for Hackage {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["hackage.haskell.org"]
+    }
+
+    // https://hackage.haskell.org/api#preferred
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(name) = hackage_package(&query.url) else {
+                query
+                    .notes
+                    .push(format!("No Hackage package in URL for {}", query.distname));
+                return Ok(Found::Nothing);
+            };
+            let request = query
+                .client
+                .get(format!(
+                    "https://hackage.haskell.org/package/{name}/preferred"
+                ))
+                .header(header::ACCEPT, "application/json");
+            // deprecated versions are left out
+            let preferred: HackagePreferred = get_json(request, &query.config.http).await?;
+            let latest = highest_version(
+                preferred.normal_version.iter().map(String::as_str),
+                &query.filter(),
+            );
+            match latest {
+                Some(version) => return Ok(Found::Version(version.into())),
+                None => query
+                    .notes
+                    .push(format!("No version for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Package name from Hackage URL, like `https://hackage.haskell.org/package/pandoc`, or
+/// `https://hackage.haskell.org/package/pandoc-3.1.9` of a single version.
+fn hackage_package(url: &Url) -> Option<&str> {
+    let mut segments = url.path_segments()?;
+    if segments.next()? != "package" {
+        return None;
+    }
+    let name = segments.next().filter(|name| !name.is_empty())?;
+    // package names don't have a component of digits only, versions do
+    match name.rsplit_once('-') {
+        Some((base, version)) if version.split('.').all(|d| d.parse::<u32>().is_ok()) => Some(base),
+        _ => Some(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hackage_package() {
+        let package = |url| hackage_package(&Url::parse(url).unwrap()).map(String::from);
+        assert_eq!(
+            package("https://hackage.haskell.org/package/pandoc").as_deref(),
+            Some("pandoc")
+        );
+        assert_eq!(
+            package("https://hackage.haskell.org/package/pandoc-types-1.23.1").as_deref(),
+            Some("pandoc-types")
+        );
+        assert_eq!(
+            package("https://hackage.haskell.org/package/base64-bytestring/").as_deref(),
+            Some("base64-bytestring")
+        );
+        assert_eq!(package("https://hackage.haskell.org/packages/"), None);
+    }
+}
@@ -0,0 +1,142 @@
+use super::{Backend, Found, Query};
+use crate::{
+    error::CheckError,
+    http::get_text,
+    version::{parse_html, select_html, Version, VersionFilter},
+};
+use futures::future::BoxFuture;
+use regex::Regex;
+use reqwest::Url;
+use std::str::FromStr;
+
+/// Versions linked from HTML page, or matched by pattern.
+pub(super) struct Html;
+
+impl Backend for Html {
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let request = query.client.get(query.site);
+            let mut body = if query.descend {
+                // new releases show up in the subdirectory, while the listing may stay the
+                // same, so it is always fetched
+                let listing = get_text(request, &query.config.http).await?;
+                let Some(url) = subdirectory(&query.url, &listing, query.stable_only) else {
+                    query
+                        .notes
+                        .push(format!("No version subdirectory for {}", query.distname));
+                    return Ok(Found::Nothing);
+                };
+                get_text(query.client.get(url), &query.config.http).await?
+            } else {
+                let Some(body) = query.get_modified(request).await? else {
+                    return Ok(Found::NotModified);
+                };
+                body
+            };
+            if let Some(selector) = query.selector()? {
+                body = select_html(&body, &selector);
+            }
+            let latest = match query.regex()? {
+                Some(pattern) => match_version(&pattern, &body, &query.filter()),
+                None => parse_html(
+                    &body,
+                    &query.filter(),
+                    &query.config.html.ignore,
+                    query.scan_text,
+                ),
+            };
+            match latest {
+                None => query
+                    .notes
+                    .push(format!("No version for {}", query.distname)),
+                Some(version) => return Ok(Found::Version(version.to_string())),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Find the highest version captured by the first group of `pattern` in `text`, accepted
+/// by `filter`.
+fn match_version(pattern: &Regex, text: &str, filter: &VersionFilter) -> Option<Version> {
+    pattern
+        .captures_iter(text)
+        .filter_map(|captures| Version::from_str(captures.get(1)?.as_str()).ok())
+        .filter(|version| filter.accepts(version))
+        .max()
+}
+
+/// The highest version subdirectory linked from directory listing at `base`, like `1.24/`
+/// or `v16/`. Links outside of `base`, like to the parent directory, are skipped.
+fn subdirectory(base: &Url, listing: &str, stable_only: bool) -> Option<Url> {
+    let mut base = base.clone();
+    if !base.path().ends_with('/') {
+        base.set_path(&format!("{}/", base.path()));
+    }
+    let href = Regex::new(r#"href="([^"?#]+/)""#).expect("valid regex");
+    href.captures_iter(listing)
+        .filter_map(|captures| base.join(captures.get(1)?.as_str()).ok())
+        .filter(|url| url.as_str().starts_with(base.as_str()) && *url != base)
+        .filter_map(|url| {
+            let name = url.path_segments()?.rev().find(|s| !s.is_empty())?;
+            let version = Version::from_str(name).ok()?;
+            (!stable_only || !version.is_prerelease()).then_some((version, url))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, url)| url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_version() {
+        let html = r#"<h2>Latest release</h2>
+<p>Version 2.4.1 (2023-07-01), see <a href="/download">downloads</a></p>
+<p>Version 2.5.0-beta (2023-08-01)</p>
+<p>Version 2.3.9 (2023-05-01)</p>
+<p>Copyright 2008-2023</p>"#;
+        let pattern = Regex::new(r"Version ([\w.-]+)").unwrap();
+        let stable_only = VersionFilter {
+            stable_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            match_version(&pattern, html, &stable_only),
+            Some(Version::new(vec![2, 4, 1]))
+        );
+        assert_eq!(
+            match_version(&pattern, html, &VersionFilter::default()),
+            Some(Version::from_str("2.5.0-beta").unwrap())
+        );
+        let pattern = Regex::new(r"Release ([\d.]+)").unwrap();
+        assert_eq!(
+            match_version(&pattern, html, &VersionFilter::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_subdirectory() {
+        let listing = r#"<a href="../">../</a>
+<a href="1.9/">1.9/</a>
+<a href="1.24/">1.24/</a>
+<a href="1.25-rc/">1.25-rc/</a>
+<a href="/pub/2.0/">2.0/</a>
+<a href="nginx-1.24.0.tar.gz">nginx-1.24.0.tar.gz</a>
+<a href="https://mirror.example.com/nginx/9.9/">mirror</a>"#;
+        let base = Url::parse("https://nginx.example.com/download").unwrap();
+        let find =
+            |stable_only| subdirectory(&base, listing, stable_only).map(|url| url.to_string());
+        assert_eq!(
+            find(true).as_deref(),
+            Some("https://nginx.example.com/download/1.24/")
+        );
+        assert_eq!(
+            find(false).as_deref(),
+            Some("https://nginx.example.com/download/1.25-rc/")
+        );
+        assert_eq!(subdirectory(&base, "<a href=\"../\">../</a>", false), None);
+    }
+}
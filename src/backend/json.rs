@@ -0,0 +1,91 @@
+use super::{Backend, Found, Query};
+use crate::error::CheckError;
+use futures::future::BoxFuture;
+use serde_json::Value;
+use std::str::FromStr;
+
+/// Version field of JSON document.
+pub(super) struct Json;
+
+impl Backend for Json {
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(path) = query.json_path else {
+                query
+                    .notes
+                    .push(format!("No JSON path for {}", query.distname));
+                return Ok(Found::Nothing);
+            };
+            let request = query.client.get(query.site);
+            let Some(body) = query.get_modified(request).await? else {
+                return Ok(Found::NotModified);
+            };
+            let document: Value = serde_json::from_str(&body)?;
+            match json_path(&document, path) {
+                Some(Value::String(version)) => return Ok(Found::Tag(version.clone())),
+                Some(Value::Number(version)) => return Ok(Found::Tag(version.to_string())),
+                Some(_) => query.notes.push(format!(
+                    "No version string at {path} for {}",
+                    query.distname
+                )),
+                None => query
+                    .notes
+                    .push(format!("No {path} in JSON for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Select value in JSON document by path: JSON pointer like `/latest/version`, or dotted
+/// path like `$.latest.version`, `tag_name` or `releases[0].name`.
+fn json_path<'a>(document: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.starts_with('/') {
+        return document.pointer(path);
+    }
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let mut value = document;
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        // name followed by array indexes, like `releases[0]`
+        let (name, mut indexes) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if !name.is_empty() {
+            value = value.get(name)?;
+        }
+        while let Some(rest) = indexes.strip_prefix('[') {
+            let (index, rest) = rest.split_once(']')?;
+            value = value.get(usize::from_str(index).ok()?)?;
+            indexes = rest;
+        }
+        if !indexes.is_empty() {
+            return None;
+        }
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_path() {
+        let document: Value = serde_json::from_str(
+            r#"{"latest": {"version": "1.2.3"}, "tag_name": "v1.2.3", "build": 42,
+            "releases": [{"name": "1.2.3", "assets": [[1, 2], [3]]}, {"name": "1.2.2"}]}"#,
+        )
+        .unwrap();
+        let select = |path| json_path(&document, path).cloned();
+        assert_eq!(select("$.latest.version"), Some("1.2.3".into()));
+        assert_eq!(select("latest.version"), Some("1.2.3".into()));
+        assert_eq!(select("/latest/version"), Some("1.2.3".into()));
+        assert_eq!(select("tag_name"), Some("v1.2.3".into()));
+        assert_eq!(select("$.releases[1].name"), Some("1.2.2".into()));
+        assert_eq!(select("releases[0].assets[0][1]"), Some(2.into()));
+        assert_eq!(select("build"), Some(42.into()));
+        assert_eq!(select("$"), Some(document.clone()));
+        assert_eq!(select("$.latest.missing"), None);
+        assert_eq!(select("releases[5].name"), None);
+        assert_eq!(select("releases[x]"), None);
+        assert_eq!(select("releases[0]x"), None);
+    }
+}
@@ -0,0 +1,122 @@
+use super::{Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+
+#[derive(Deserialize)]
+struct KernelReleases {
+    latest_stable: KernelVersion,
+    releases: Vec<KernelRelease>,
+}
+
+#[derive(Deserialize)]
+struct KernelVersion {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct KernelRelease {
+    /// `mainline`, `stable`, `longterm` or `linux-next`.
+    moniker: String,
+    version: String,
+}
+
+/// Linux kernel releases; URL fragment selects the branch, like `#mainline`, `#longterm` or
+/// `#6.1`. Default is the latest stable release.
+pub(super) struct Kernel;
+
+impl Backend for Kernel {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["www.kernel.org", "kernel.org"]
+    }
+
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let releases: KernelReleases = get_json(
+                query.client.get("https://www.kernel.org/releases.json"),
+                &query.config.http,
+            )
+            .await?;
+            let branch = query.url.fragment().unwrap_or_default();
+            match kernel_version(&releases, branch, query.version) {
+                Some(version) => return Ok(Found::Version(version.into())),
+                None => query.notes.push(format!(
+                    "No kernel release of branch {branch} for {}",
+                    query.distname
+                )),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Kernel version of `branch`: `mainline`, `stable` (also when empty), `longterm` of the
+/// same series as `current`, or a series like `6.1`.
+fn kernel_version<'a>(
+    releases: &'a KernelReleases,
+    branch: &str,
+    current: &str,
+) -> Option<&'a str> {
+    let series = match branch {
+        "" | "stable" => return Some(&releases.latest_stable.version),
+        "mainline" => {
+            return releases
+                .releases
+                .iter()
+                .find(|release| release.moniker == "mainline")
+                .map(|release| release.version.as_str());
+        }
+        // `6.1.62` -> `6.1`
+        "longterm" => {
+            let mut components = current.split('.');
+            format!("{}.{}", components.next()?, components.next()?)
+        }
+        series => series.to_string(),
+    };
+    releases
+        .releases
+        .iter()
+        .map(|release| release.version.as_str())
+        .find(|version| {
+            version
+                .strip_prefix(&series)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernel_version() {
+        let releases: KernelReleases = serde_json::from_str(
+            r#"{
+                "latest_stable": {"version": "6.6.1"},
+                "releases": [
+                    {"moniker": "mainline", "version": "6.7-rc1"},
+                    {"moniker": "stable", "version": "6.6.1"},
+                    {"moniker": "longterm", "version": "6.1.62"},
+                    {"moniker": "longterm", "version": "5.15.138"},
+                    {"moniker": "linux-next", "version": "next-20231115"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(kernel_version(&releases, "", "6.5.0"), Some("6.6.1"));
+        assert_eq!(kernel_version(&releases, "stable", "6.5.0"), Some("6.6.1"));
+        assert_eq!(
+            kernel_version(&releases, "mainline", "6.6"),
+            Some("6.7-rc1")
+        );
+        assert_eq!(
+            kernel_version(&releases, "longterm", "6.1.60"),
+            Some("6.1.62")
+        );
+        assert_eq!(
+            kernel_version(&releases, "5.15", "5.15.1"),
+            Some("5.15.138")
+        );
+        // 6.10 is not 6.1
+        assert_eq!(kernel_version(&releases, "6.10", "6.10.1"), None);
+    }
+}
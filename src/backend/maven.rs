@@ -0,0 +1,147 @@
+use super::{highest_version, Backend, Found, Query};
+use crate::{error::CheckError, http::get_text};
+use futures::future::BoxFuture;
+use regex::Regex;
+use reqwest::Url;
+
+/// Maven repository metadata; Maven Central or Gradle plugin portal.
+pub(super) struct Maven;
+
+impl Backend for Maven {
+    fn hosts(&self) -> &'static [&'static str] {
+        &[
+            "search.maven.org",
+            "central.sonatype.com",
+            "mvnrepository.com",
+            "repo1.maven.org",
+            "repo.maven.apache.org",
+            "plugins.gradle.org",
+        ]
+    }
+
+    fn url(&self, site: &str) -> Result<Url, url::ParseError> {
+        // Maven coordinates, used as they are
+        Url::parse(site).or_else(|_| Url::parse("https://repo1.maven.org/maven2/"))
+    }
+
+    // https://maven.apache.org/repositories/metadata.html
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(metadata_url) = maven_metadata_url(query.site) else {
+                query.notes.push(format!(
+                    "No Maven artifact in {} for {}",
+                    query.site, query.distname
+                ));
+                return Ok(Found::Nothing);
+            };
+            let metadata = get_text(query.client.get(metadata_url), &query.config.http).await?;
+            let version = Regex::new("<version>([^<]+)</version>").expect("valid regex");
+            let latest = highest_version(
+                version
+                    .captures_iter(&metadata)
+                    .filter_map(|captures| captures.get(1))
+                    .map(|version| version.as_str())
+                    // development builds
+                    .filter(|version| !version.ends_with("-SNAPSHOT")),
+                &query.filter(),
+            );
+            match latest {
+                Some(version) => return Ok(Found::Version(version.into())),
+                None => query
+                    .notes
+                    .push(format!("No release for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// URL of `maven-metadata.xml` listing versions of a Maven artifact, from its coordinates
+/// `groupId:artifactId`, its page on a Maven Central site, its directory in the repository,
+/// or a Gradle plugin page like `https://plugins.gradle.org/plugin/org.example.plugin`.
+fn maven_metadata_url(site: &str) -> Option<String> {
+    const CENTRAL: &str = "https://repo1.maven.org/maven2";
+    let metadata_url = |repository: &str, group: &str, artifact: &str| {
+        format!(
+            "{repository}/{}/{artifact}/maven-metadata.xml",
+            group.replace('.', "/")
+        )
+    };
+    let Some((_, rest)) = site.split_once("://") else {
+        let mut coordinates = site.split(':');
+        let group = coordinates.next().filter(|group| !group.is_empty())?;
+        let artifact = coordinates.next().filter(|artifact| !artifact.is_empty())?;
+        return Some(metadata_url(CENTRAL, group, artifact));
+    };
+    let mut segments = rest.split('/').filter(|segment| !segment.is_empty());
+    match segments.next()? {
+        "search.maven.org" | "central.sonatype.com" | "mvnrepository.com" => {
+            if segments.next()? != "artifact" {
+                return None;
+            }
+            let group = segments.next()?;
+            Some(metadata_url(CENTRAL, group, segments.next()?))
+        }
+        "repo1.maven.org" | "repo.maven.apache.org" => {
+            if segments.next()? != "maven2" {
+                return None;
+            }
+            // artifact directory, or its metadata file
+            let mut path: Vec<&str> = segments.collect();
+            if path.last() == Some(&"maven-metadata.xml") {
+                path.pop();
+            }
+            let artifact = path.pop()?;
+            (!path.is_empty()).then(|| metadata_url(CENTRAL, &path.join("."), artifact))
+        }
+        "plugins.gradle.org" => {
+            if segments.next()? != "plugin" {
+                return None;
+            }
+            let id = segments.next()?;
+            Some(metadata_url(
+                "https://plugins.gradle.org/m2",
+                id,
+                &format!("{id}.gradle.plugin"),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maven_metadata_url() {
+        let slf4j = Some("https://repo1.maven.org/maven2/org/slf4j/slf4j-api/maven-metadata.xml");
+        assert_eq!(maven_metadata_url("org.slf4j:slf4j-api").as_deref(), slf4j);
+        assert_eq!(
+            maven_metadata_url("https://search.maven.org/artifact/org.slf4j/slf4j-api").as_deref(),
+            slf4j
+        );
+        assert_eq!(
+            maven_metadata_url("https://central.sonatype.com/artifact/org.slf4j/slf4j-api/2.0.9")
+                .as_deref(),
+            slf4j
+        );
+        assert_eq!(
+            maven_metadata_url("https://repo1.maven.org/maven2/org/slf4j/slf4j-api/").as_deref(),
+            slf4j
+        );
+        assert_eq!(
+            maven_metadata_url("https://plugins.gradle.org/plugin/com.github.ben-manes.versions")
+                .as_deref(),
+            Some(
+                "https://plugins.gradle.org/m2/com/github/ben-manes/versions/\
+                com.github.ben-manes.versions.gradle.plugin/maven-metadata.xml"
+            )
+        );
+        assert_eq!(maven_metadata_url("slf4j"), None);
+        assert_eq!(
+            maven_metadata_url("https://repo1.maven.org/maven2/junit/"),
+            None
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use super::{is_prerelease, Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+use reqwest::Url;
+
+#[derive(Deserialize)]
+struct MetaCpanRelease {
+    version: String,
+}
+
+/// MetaCPAN latest release.
+pub(super) struct MetaCpan;
+
+impl Backend for MetaCpan {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["metacpan.org"]
+    }
+
+    // https://github.com/metacpan/metacpan-api/blob/master/docs/API-docs.md
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(api) = metacpan_api_url(&query.url) else {
+                query.notes.push(format!(
+                    "No module or distribution in URL for {}",
+                    query.distname
+                ));
+                return Ok(Found::Nothing);
+            };
+            let release: MetaCpanRelease =
+                get_json(query.client.get(api), &query.config.http).await?;
+            if query.stable_only && is_prerelease(&release.version) {
+                query
+                    .notes
+                    .push(format!("No stable version for {}", query.distname));
+                return Ok(Found::Nothing);
+            }
+            Ok(Found::Tag(release.version))
+        })
+    }
+}
+
+/// MetaCPAN API URL of the latest release, from a module page like
+/// `https://metacpan.org/pod/Data::Dumper` or a distribution page like
+/// `https://metacpan.org/dist/Moose`.
+fn metacpan_api_url(url: &Url) -> Option<String> {
+    let mut segments = url.path_segments()?;
+    let kind = segments.next()?;
+    let name = segments.next().filter(|name| !name.is_empty())?;
+    match kind {
+        "pod" => Some(format!(
+            "https://fastapi.metacpan.org/v1/download_url/{name}"
+        )),
+        "dist" | "release" => Some(format!("https://fastapi.metacpan.org/v1/release/{name}")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metacpan_api_url() {
+        let api_url = |url| metacpan_api_url(&Url::parse(url).unwrap());
+        assert_eq!(
+            api_url("https://metacpan.org/pod/Data::Dumper").as_deref(),
+            Some("https://fastapi.metacpan.org/v1/download_url/Data::Dumper")
+        );
+        assert_eq!(
+            api_url("https://metacpan.org/dist/Moose").as_deref(),
+            Some("https://fastapi.metacpan.org/v1/release/Moose")
+        );
+        assert_eq!(api_url("https://metacpan.org/author/ETHER"), None);
+    }
+}
@@ -0,0 +1,398 @@
+mod arch;
+mod bitbucket;
+mod crates;
+mod custom;
+mod dockerhub;
+mod fdroid;
+mod feed;
+mod ftp;
+mod git;
+mod gitea;
+mod github;
+mod gitlab;
+mod gnu;
+mod hackage;
+mod html;
+mod json;
+mod kernel;
+mod maven;
+mod metacpan;
+mod npm;
+mod nuget;
+mod packagist;
+mod pecl;
+mod pypi;
+mod redirect;
+mod rubygems;
+mod sourceforge;
+
+pub use custom::Custom;
+
+use crate::{
+    config::Config,
+    error::CheckError,
+    http::{check_status, send},
+    source::Source,
+    version::{Selector, Version, VersionFilter},
+};
+use futures::future::BoxFuture;
+use regex::Regex;
+use reqwest::{header, Client, RequestBuilder, StatusCode, Url};
+use std::str::FromStr;
+
+/// Name and backend of every source, in the order master sites are matched against them.
+const REGISTRY: [(Source, &str, &dyn Backend); 27] = [
+    // any host, told apart by URL scheme or path
+    (Source::Git, "git", &git::Git),
+    (Source::Feed, "feed", &feed::Feed),
+    (Source::Ftp, "ftp", &ftp::Ftp),
+    (Source::Pypi, "pypi", &pypi::Pypi),
+    (Source::Crates, "crates", &crates::Crates),
+    (Source::Npm, "npm", &npm::Npm),
+    (Source::RubyGems, "rubygems", &rubygems::RubyGems),
+    (Source::Hackage, "hackage", &hackage::Hackage),
+    (Source::MetaCpan, "metacpan", &metacpan::MetaCpan),
+    (Source::DockerHub, "dockerhub", &dockerhub::DockerHub),
+    (Source::Packagist, "packagist", &packagist::Packagist),
+    (Source::Pecl, "pecl", &pecl::Pecl),
+    (Source::Maven, "maven", &maven::Maven),
+    (Source::NuGet, "nuget", &nuget::NuGet),
+    (Source::Gnu, "gnu", &gnu::Gnu),
+    (Source::Kernel, "kernel", &kernel::Kernel),
+    (Source::Arch, "arch", &arch::Arch),
+    (Source::FDroid, "fdroid", &fdroid::FDroid),
+    (Source::GitHub, "github", &github::GitHub),
+    (Source::GitLab, "gitlab", &gitlab::GitLab),
+    (Source::Gitea, "gitea", &gitea::Gitea),
+    (Source::Bitbucket, "bitbucket", &bitbucket::Bitbucket),
+    (Source::Sourcehut, "sourcehut", &git::Sourcehut),
+    (
+        Source::SourceForge,
+        "sourceforge",
+        &sourceforge::SourceForge,
+    ),
+    // any host, so only after the ones above
+    (Source::Redirect, "redirect", &redirect::Redirect),
+    (Source::Html, "html", &html::Html),
+    (Source::Json, "json", &json::Json),
+];
+
+/// Source whose backend recognizes master site `url`, if any.
+#[must_use]
+pub fn detect(url: &Url) -> Option<Source> {
+    REGISTRY
+        .iter()
+        .find(|(_, _, backend)| backend.detect(url))
+        .map(|(source, _, _)| *source)
+}
+
+/// Backend looking for versions from `source`.
+#[must_use]
+pub fn backend(source: Source) -> &'static dyn Backend {
+    REGISTRY
+        .iter()
+        .find(|(registered, _, _)| *registered == source)
+        .map(|(_, _, backend)| *backend)
+        .expect("every source has a backend")
+}
+
+/// Name of `source`, as in configuration and on command line.
+#[must_use]
+pub fn name(source: Source) -> &'static str {
+    REGISTRY
+        .iter()
+        .find(|(registered, _, _)| *registered == source)
+        .map(|(_, name, _)| *name)
+        .expect("every source has a name")
+}
+
+/// Source named `name`, if any.
+#[must_use]
+pub fn named(name: &str) -> Option<Source> {
+    REGISTRY
+        .iter()
+        .find(|(_, registered, _)| *registered == name)
+        .map(|(source, _, _)| *source)
+}
+
+/// Names of all sources.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    REGISTRY.iter().map(|(_, name, _)| *name)
+}
+
+/// Way of looking for upstream versions at some kind of site.
+pub trait Backend: Sync {
+    /// Host names of master sites served by this backend.
+    fn hosts(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Whether master site belongs to this backend; by default, if its host is one of
+    /// `hosts`.
+    fn detect(&self, url: &Url) -> bool {
+        url.domain()
+            .is_some_and(|domain| self.hosts().contains(&domain))
+    }
+
+    /// URL of master site or mirror. Backends accepting other forms, like bare package
+    /// names, override it.
+    fn url(&self, site: &str) -> Result<Url, url::ParseError> {
+        Url::parse(site)
+    }
+
+    /// Look for the latest upstream version. Remarks, like no suitable version found, are
+    /// added to `query.notes`.
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>>;
+}
+
+/// Package being checked, and the site to look at.
+pub struct Query<'a> {
+    pub client: &'a Client,
+    pub config: &'a Config,
+    /// Master site or one of its mirrors, as given.
+    pub site: &'a str,
+    /// `site` parsed by backend.
+    pub url: Url,
+    pub distname: &'a str,
+    /// Latest upstream version known.
+    pub version: &'a str,
+    /// Ignore pre-release versions.
+    pub stable_only: bool,
+    /// Regular expression with one capture group extracting version.
+    pub pattern: Option<&'a str>,
+    /// Path of version field in JSON document.
+    pub json_path: Option<&'a str>,
+    /// Look for versions in text of HTML page too, not only in links.
+    pub scan_text: bool,
+    /// Look at all releases, not only the latest one.
+    pub all_releases: bool,
+    /// CSS selector of HTML page parts to look at.
+    pub selector: Option<&'a str>,
+    /// Look in the highest version subdirectory of directory listing.
+    pub descend: bool,
+    /// Versions to skip.
+    pub ignore: Option<&'a Regex>,
+    /// Prefix of release tags.
+    pub tag_prefix: Option<&'a str>,
+    /// Suffix of release tags.
+    pub tag_suffix: Option<&'a str>,
+    /// Epoch given to versions found which have none; 0 means none.
+    pub epoch: i32,
+    /// `ETag` header of the previous response; replaced by `get_modified`.
+    pub etag: Option<String>,
+    /// `Last-Modified` header of the previous response; replaced by `get_modified`.
+    pub last_modified: Option<String>,
+    pub notes: &'a mut Vec<String>,
+}
+
+/// What backend found upstream.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Found {
+    /// Version as published.
+    Version(String),
+    /// Release tag; any prefix like `v` or `release-` is dropped.
+    Tag(String),
+    /// No suitable version, which is noted.
+    Nothing,
+    /// Document not modified since the previous check.
+    NotModified,
+}
+
+impl Query<'_> {
+    /// Criteria for versions found upstream: pre-releases and versions matching `ignore` are
+    /// skipped, and versions must follow the scheme of the version known.
+    #[must_use]
+    pub fn filter(&self) -> VersionFilter {
+        VersionFilter {
+            stable_only: self.stable_only,
+            current: Version::from_str(self.version).ok(),
+            ignore: self.ignore.cloned(),
+            epoch: self.epoch,
+        }
+    }
+
+    /// Version pattern compiled.
+    pub fn regex(&self) -> Result<Option<Regex>, regex::Error> {
+        self.pattern.map(Regex::new).transpose()
+    }
+
+    /// Version part of a release tag: the first group captured by `pattern`, or the tag
+    /// without `tag_prefix` and `tag_suffix`. Tags not matching `pattern`, or without the
+    /// prefix or suffix, yield `None`.
+    pub fn tag_version<'t>(&self, pattern: Option<&Regex>, tag: &'t str) -> Option<&'t str> {
+        if pattern.is_some() {
+            return tag_version(pattern, tag);
+        }
+        let tag = match self.tag_prefix {
+            Some(prefix) => tag.strip_prefix(prefix)?,
+            None => tag,
+        };
+        match self.tag_suffix {
+            Some(suffix) => tag.strip_suffix(suffix),
+            None => Some(tag),
+        }
+    }
+
+    /// CSS selector parsed.
+    pub fn selector(&self) -> Result<Option<Selector>, CheckError> {
+        self.selector
+            .map(|selector| Selector::from_str(selector).map_err(CheckError::Selector))
+            .transpose()
+    }
+
+    /// Send conditional request with validators of the previous response, and keep
+    /// validators of this one. Returns `None` if the document was not modified.
+    pub async fn get_modified(
+        &mut self,
+        mut request: RequestBuilder,
+    ) -> Result<Option<String>, CheckError> {
+        if let Some(etag) = &self.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+        let response = send(request, &self.config.http).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        let response = check_status(response)?;
+        let validator = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string)
+        };
+        self.etag = validator(header::ETAG);
+        self.last_modified = validator(header::LAST_MODIFIED);
+        Ok(Some(response.text().await?))
+    }
+}
+
+/// `owner/repo` path of GitHub, Gitea, Bitbucket or sourcehut repository, from a URL of the
+/// repository or any page in it, like `https://github.com/owner/repo/releases`.
+fn repository(url: &Url) -> Option<String> {
+    let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+    let owner = segments.next()?;
+    let repo = segments.next()?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    Some(format!("{owner}/{repo}"))
+}
+
+/// Host of `url` is one of the backend `hosts` or configured `instances`, so it may be sent
+/// the access token of the backend.
+fn is_instance(url: &Url, hosts: &[&str], instances: &[String]) -> bool {
+    url.host_str().is_some_and(|host| {
+        hosts.contains(&host) || instances.iter().any(|instance| instance == host)
+    })
+}
+
+/// Check if version string carries a pre-release marker.
+fn is_prerelease(version: &str) -> bool {
+    Version::from_str(version).is_ok_and(|version| version.is_prerelease())
+}
+
+/// Highest of given version strings, accepted by `filter`.
+fn highest_version<'a>(
+    versions: impl Iterator<Item = &'a str>,
+    filter: &VersionFilter,
+) -> Option<&'a str> {
+    versions
+        .filter_map(|s| Version::from_str(s).ok().map(|version| (version, s)))
+        .filter(|(version, _)| filter.accepts(version))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, s)| s)
+}
+
+/// Version part of a tag: the first group captured by `pattern`, or the whole tag without
+/// pattern. Tags not matching `pattern` yield `None`.
+fn tag_version<'a>(pattern: Option<&Regex>, tag: &'a str) -> Option<&'a str> {
+    match pattern {
+        Some(pattern) => Some(pattern.captures(tag)?.get(1)?.as_str()),
+        None => Some(tag),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        for (i, (source, name, _)) in REGISTRY.iter().enumerate() {
+            assert!(
+                REGISTRY[i + 1..]
+                    .iter()
+                    .all(|(other, other_name, _)| other != source && other_name != name),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_repository() {
+        let repo = |url| repository(&Url::parse(url).unwrap());
+        for url in [
+            "https://github.com/owner/repo",
+            "https://github.com/owner/repo/",
+            "https://github.com/owner/repo/releases",
+            "https://github.com/owner/repo.git",
+            "https://codeberg.org/owner/repo/releases/tag/v1.0",
+            "https://bitbucket.org/owner/repo/downloads/",
+            "https://git.sr.ht/owner/repo/refs",
+        ] {
+            assert_eq!(repo(url).as_deref(), Some("owner/repo"), "{url}");
+        }
+        assert_eq!(repo("https://github.com/owner"), None);
+    }
+
+    #[test]
+    fn test_is_instance() {
+        let instances = ["gitlab.example.com".to_string()];
+        let instance = |url| is_instance(&Url::parse(url).unwrap(), &["gitlab.com"], &instances);
+        assert!(instance("https://gitlab.com/owner/repo"));
+        assert!(instance("https://gitlab.example.com/owner/repo"));
+        assert!(!instance("https://example.com/owner/repo"));
+        assert!(!instance("https://gitlab.com.example.com/owner/repo"));
+    }
+
+    #[test]
+    fn test_highest_version() {
+        let stable_only = VersionFilter {
+            stable_only: true,
+            ..Default::default()
+        };
+        let tags = ["v1.2.0", "v1.3.0rc1", "v1.3.0-beta", "v1.1.9", "nightly"];
+        assert_eq!(
+            highest_version(tags.into_iter(), &stable_only),
+            Some("v1.2.0")
+        );
+        assert_eq!(
+            highest_version(["2.0.0.dev3"].into_iter(), &stable_only),
+            None
+        );
+        assert_eq!(
+            highest_version(
+                ["v1.3.0rc1", "v1.2.0"].into_iter(),
+                &VersionFilter::default()
+            ),
+            Some("v1.3.0rc1")
+        );
+        // build numbers and snapshot dates don't look like a release of 1.x
+        let semver = VersionFilter {
+            current: Version::from_str("1.2.0").ok(),
+            ..Default::default()
+        };
+        let tags = ["v1.2.0", "v1.3.0", "build-20240101", "2019.1"];
+        assert_eq!(highest_version(tags.into_iter(), &semver), Some("v1.3.0"));
+    }
+
+    #[test]
+    fn test_tag_version() {
+        assert_eq!(tag_version(None, "v1.2.3"), Some("v1.2.3"));
+        let pattern = Regex::new(r"^cli-v(.+)$").unwrap();
+        assert_eq!(tag_version(Some(&pattern), "cli-v2.0.1"), Some("2.0.1"));
+        assert_eq!(tag_version(Some(&pattern), "lib-v3.1.0"), None);
+    }
+}
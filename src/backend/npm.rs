@@ -0,0 +1,106 @@
+use super::{is_prerelease, Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+use reqwest::{header, Url};
+
+#[derive(Deserialize)]
+struct NpmPackage {
+    #[serde(rename = "dist-tags")]
+    dist_tags: NpmDistTags,
+}
+
+#[derive(Deserialize)]
+struct NpmDistTags {
+    latest: Option<String>,
+}
+
+/// npm registry, `latest` distribution tag.
+pub(super) struct Npm;
+
+impl Backend for Npm {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["www.npmjs.com", "npmjs.com", "registry.npmjs.org"]
+    }
+
+    fn url(&self, site: &str) -> Result<Url, url::ParseError> {
+        match Url::parse(site) {
+            // bare package name
+            Err(url::ParseError::RelativeUrlWithoutBase) => {
+                Url::parse("https://www.npmjs.com/package/")?.join(site)
+            }
+            url => url,
+        }
+    }
+
+    // https://github.com/npm/registry/blob/master/docs/responses/package-metadata.md
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let request = query
+                .client
+                .get(npm_registry_url(&query.url))
+                // abbreviated metadata, instead of every release in full
+                .header(header::ACCEPT, "application/vnd.npm.install-v1+json");
+            let npm_package: NpmPackage = get_json(request, &query.config.http).await?;
+            match npm_package.dist_tags.latest {
+                Some(version) if query.stable_only && is_prerelease(&version) => {
+                    query
+                        .notes
+                        .push(format!("No stable version for {}", query.distname));
+                }
+                Some(version) => return Ok(Found::Version(version)),
+                None => query
+                    .notes
+                    .push(format!("No latest version for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Registry URL of npm package, from its page on npmjs.com, like
+/// `https://www.npmjs.com/package/name/v/1.0.0`, or in the registry, like
+/// `https://registry.npmjs.org/name/latest`.
+fn npm_registry_url(url: &Url) -> String {
+    let path = url.path().trim_matches('/');
+    let path = path.strip_prefix("package/").unwrap_or(path);
+    // anything after the name is a version or tag
+    let segments = if path.starts_with('@') { 2 } else { 1 };
+    let name: Vec<&str> = path.splitn(segments + 1, '/').take(segments).collect();
+    // scoped package name is a single path segment
+    format!("https://registry.npmjs.org/{}", name.join("%2F"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npm_registry_url() {
+        let registry_url = |url| npm_registry_url(&Url::parse(url).unwrap());
+        assert_eq!(
+            registry_url("https://www.npmjs.com/package/typescript"),
+            "https://registry.npmjs.org/typescript"
+        );
+        assert_eq!(
+            registry_url("https://www.npmjs.com/package/@types/node"),
+            "https://registry.npmjs.org/@types%2Fnode"
+        );
+        assert_eq!(
+            registry_url("https://registry.npmjs.org/typescript"),
+            "https://registry.npmjs.org/typescript"
+        );
+        assert_eq!(
+            registry_url("https://registry.npmjs.org/typescript/latest"),
+            "https://registry.npmjs.org/typescript"
+        );
+        assert_eq!(
+            registry_url("https://www.npmjs.com/package/@types/node/v/20.0.0"),
+            "https://registry.npmjs.org/@types%2Fnode"
+        );
+        // bare package name
+        assert_eq!(
+            registry_url(Npm.url("@types/node").unwrap().as_str()),
+            "https://registry.npmjs.org/@types%2Fnode"
+        );
+    }
+}
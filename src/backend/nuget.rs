@@ -0,0 +1,58 @@
+use super::{highest_version, Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+
+#[derive(Deserialize)]
+struct NuGetIndex {
+    versions: Vec<String>,
+}
+
+/// NuGet package versions, highest stable one.
+pub(super) struct NuGet;
+
+impl Backend for NuGet {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["www.nuget.org", "nuget.org"]
+    }
+
+    // https://learn.microsoft.com/en-us/nuget/api/package-base-address-resource
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(id) = query
+                .url
+                .path_segments()
+                .and_then(|mut segments| segments.nth(1))
+                .filter(|id| !id.is_empty())
+            else {
+                query
+                    .notes
+                    .push(format!("No NuGet package in URL for {}", query.distname));
+                return Ok(Found::Nothing);
+            };
+            let index: NuGetIndex = get_json(
+                query.client.get(format!(
+                    "https://api.nuget.org/v3-flatcontainer/{}/index.json",
+                    id.to_ascii_lowercase()
+                )),
+                &query.config.http,
+            )
+            .await?;
+            // any label after a hyphen marks a pre-release, not only known ones
+            let latest = highest_version(
+                index
+                    .versions
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|version| !version.contains('-')),
+                &query.filter(),
+            );
+            match latest {
+                Some(version) => return Ok(Found::Version(version.into())),
+                None => query
+                    .notes
+                    .push(format!("No stable version for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use super::{highest_version, Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+use reqwest::Url;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+struct PackagistMetadata {
+    /// Versions of the package, by its name.
+    packages: HashMap<String, Vec<PackagistVersion>>,
+}
+
+#[derive(Deserialize)]
+struct PackagistVersion {
+    version: String,
+}
+
+/// Packagist (Composer) package versions.
+pub(super) struct Packagist;
+
+impl Backend for Packagist {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["packagist.org"]
+    }
+
+    // https://packagist.org/apidoc#get-package-metadata-v2
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(name) = packagist_package(&query.url) else {
+                query.notes.push(format!(
+                    "No Packagist package in URL for {}",
+                    query.distname
+                ));
+                return Ok(Found::Nothing);
+            };
+            let metadata: PackagistMetadata = get_json(
+                query
+                    .client
+                    .get(format!("https://repo.packagist.org/p2/{name}.json")),
+                &query.config.http,
+            )
+            .await?;
+            let versions = metadata.packages.get(&name).map(Vec::as_slice);
+            // branches like `dev-main` are not versions
+            let latest = highest_version(
+                versions
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|version| version.version.as_str())
+                    .filter(|version| !version.starts_with("dev-")),
+                &query.filter(),
+            );
+            match latest {
+                Some(version) => return Ok(Found::Tag(version.into())),
+                None => query
+                    .notes
+                    .push(format!("No version for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// `vendor/name` of Packagist package, from its page like
+/// `https://packagist.org/packages/monolog/monolog`.
+fn packagist_package(url: &Url) -> Option<String> {
+    let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+    if segments.next()? != "packages" {
+        return None;
+    }
+    let vendor = segments.next()?;
+    Some(format!("{vendor}/{}", segments.next()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packagist_package() {
+        let package = |url| packagist_package(&Url::parse(url).unwrap());
+        assert_eq!(
+            package("https://packagist.org/packages/monolog/monolog").as_deref(),
+            Some("monolog/monolog")
+        );
+        assert_eq!(
+            package("https://packagist.org/packages/symfony/console#v7.0.0").as_deref(),
+            Some("symfony/console")
+        );
+        assert_eq!(package("https://packagist.org/packages/monolog"), None);
+    }
+}
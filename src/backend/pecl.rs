@@ -0,0 +1,49 @@
+use super::{Backend, Found, Query};
+use crate::{error::CheckError, http::get_text};
+use futures::future::BoxFuture;
+
+/// PECL REST API, latest or stable release.
+pub(super) struct Pecl;
+
+impl Backend for Pecl {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["pecl.php.net"]
+    }
+
+    // https://pear.php.net/manual/en/core.rest.php
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(name) = query
+                .url
+                .path_segments()
+                .and_then(|mut segments| segments.nth(1))
+                .filter(|name| !name.is_empty())
+            else {
+                query
+                    .notes
+                    .push(format!("No PECL package in URL for {}", query.distname));
+                return Ok(Found::Nothing);
+            };
+            let state = if query.stable_only {
+                "stable"
+            } else {
+                "latest"
+            };
+            let version = get_text(
+                query.client.get(format!(
+                    "https://pecl.php.net/rest/r/{}/{state}.txt",
+                    name.to_ascii_lowercase()
+                )),
+                &query.config.http,
+            )
+            .await?;
+            match version.trim() {
+                "" => query
+                    .notes
+                    .push(format!("No {state} version for {}", query.distname)),
+                version => return Ok(Found::Version(version.into())),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
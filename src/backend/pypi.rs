@@ -0,0 +1,58 @@
+use super::{highest_version, is_prerelease, Backend, Found, Query};
+use crate::error::CheckError;
+use futures::future::BoxFuture;
+use serde::de::IgnoredAny;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+struct PypiProject {
+    info: PypiProjectInfo,
+    /// Files of every release, by version.
+    #[serde(default)]
+    releases: HashMap<String, IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+struct PypiProjectInfo {
+    version: String,
+}
+
+/// PyPI JSON API.
+pub(super) struct Pypi;
+
+impl Backend for Pypi {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["pypi.org", "pypi.python.org"]
+    }
+
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(project) = query.url.path_segments().and_then(Iterator::last) else {
+                return Ok(Found::Nothing);
+            };
+            let request = query
+                .client
+                .get(format!("https://pypi.org/pypi/{project}/json"));
+            let Some(body) = query.get_modified(request).await? else {
+                return Ok(Found::NotModified);
+            };
+            let pypi_project: PypiProject = serde_json::from_str(&body)?;
+            let mut version = pypi_project.info.version.as_str();
+            if query.stable_only && is_prerelease(version) {
+                match highest_version(
+                    pypi_project.releases.keys().map(String::as_str),
+                    &query.filter(),
+                ) {
+                    Some(stable) => version = stable,
+                    None => {
+                        query
+                            .notes
+                            .push(format!("No stable version for {}", query.distname));
+                        return Ok(Found::Nothing);
+                    }
+                }
+            }
+            Ok(Found::Version(version.into()))
+        })
+    }
+}
@@ -0,0 +1,95 @@
+use super::{tag_version, Backend, Found, Query};
+use crate::{
+    error::CheckError,
+    http::{check_status, send},
+    version::{is_ignored, Version},
+};
+use futures::future::BoxFuture;
+use regex::Regex;
+use reqwest::Url;
+use std::str::FromStr;
+
+/// Version in URL that master site redirects to, like `https://example.com/download/latest`
+/// redirecting to `https://example.com/files/tool-1.2.3.tar.gz`.
+pub(super) struct Redirect;
+
+impl Backend for Redirect {
+    fn detect(&self, url: &Url) -> bool {
+        url.path_segments()
+            .and_then(|segments| segments.rev().find(|segment| !segment.is_empty()))
+            == Some("latest")
+    }
+
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            // body, possibly a large file, is not read
+            let response =
+                check_status(send(query.client.get(query.site), &query.config.http).await?)?;
+            let location = response.url().clone();
+            drop(response);
+            let pattern = query.regex()?;
+            match url_version(&location, pattern.as_ref(), &query.config.html.ignore) {
+                Some(version) if query.filter().accepts(&version) => {
+                    return Ok(Found::Version(version.to_string()));
+                }
+                Some(version) => query.notes.push(format!(
+                    "Version {version} at {location} not accepted for {}",
+                    query.distname
+                )),
+                None => query
+                    .notes
+                    .push(format!("No version in {location} for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Version in `url`: the first group captured by `pattern`, or the last path segment holding
+/// a version, skipping ones with `ignored` suffixes.
+fn url_version(url: &Url, pattern: Option<&Regex>, ignored: &[String]) -> Option<Version> {
+    match pattern {
+        Some(pattern) => Version::from_str(tag_version(Some(pattern), url.as_str())?).ok(),
+        None => url
+            .path_segments()?
+            .rev()
+            .filter(|segment| !segment.is_empty() && !is_ignored(segment, ignored))
+            .find_map(|segment| Version::from_str(segment).ok()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_version() {
+        let ignored = crate::config::HtmlConfig::default().ignore;
+        let version = |url, pattern: Option<&Regex>| {
+            url_version(&Url::parse(url).unwrap(), pattern, &ignored).map(|v| v.to_string())
+        };
+        assert_eq!(
+            version("https://example.com/files/tool-1.2.3.tar.gz", None).as_deref(),
+            Some("1.2.3")
+        );
+        // file name without version
+        assert_eq!(
+            version(
+                "https://example.com/releases/download/v2.0.1/tool.zip",
+                None
+            )
+            .as_deref(),
+            Some("2.0.1")
+        );
+        assert_eq!(version("https://example.com/files/tool.zip", None), None);
+        let pattern = Regex::new(r"/v([\d.]+)/").unwrap();
+        assert_eq!(
+            version(
+                "https://example.com/3.1/releases/v3.1.4/tool-x64.zip",
+                Some(&pattern)
+            )
+            .as_deref(),
+            Some("3.1.4")
+        );
+    }
+}
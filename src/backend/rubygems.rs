@@ -0,0 +1,47 @@
+use super::{is_prerelease, Backend, Found, Query};
+use crate::{error::CheckError, http::get_json};
+use futures::future::BoxFuture;
+
+#[derive(Deserialize)]
+struct RubyGemsVersion {
+    version: String,
+}
+
+/// RubyGems API.
+pub(super) struct RubyGems;
+
+impl Backend for RubyGems {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["rubygems.org"]
+    }
+
+    // https://guides.rubygems.org/rubygems-org-api/
+    // RubyGems rejects requests without user agent; `http_client` sets one.
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(gem) = query.url.path_segments().and_then(Iterator::last) else {
+                return Ok(Found::Nothing);
+            };
+            let latest: RubyGemsVersion = get_json(
+                query.client.get(format!(
+                    "https://rubygems.org/api/v1/versions/{gem}/latest.json"
+                )),
+                &query.config.http,
+            )
+            .await?;
+            // reported for gems that don't exist
+            if latest.version == "unknown" {
+                query
+                    .notes
+                    .push(format!("No version for {}", query.distname));
+            } else if query.stable_only && is_prerelease(&latest.version) {
+                query
+                    .notes
+                    .push(format!("No stable version for {}", query.distname));
+            } else {
+                return Ok(Found::Version(latest.version));
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
@@ -0,0 +1,163 @@
+use super::{Backend, Found, Query};
+use crate::{
+    error::CheckError,
+    http::{get_json, get_text},
+    version::{is_ignored, Version, VersionFilter},
+};
+use futures::future::BoxFuture;
+use regex::Regex;
+use reqwest::Url;
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+struct SourceForgeBestRelease {
+    release: Option<SourceForgeRelease>,
+}
+
+#[derive(Deserialize)]
+struct SourceForgeRelease {
+    /// Path of the recommended download, like `/zsh/5.9/zsh-5.9.tar.xz`.
+    filename: String,
+}
+
+/// SourceForge best release, or project files.
+pub(super) struct SourceForge;
+
+impl Backend for SourceForge {
+    fn hosts(&self) -> &'static [&'static str] {
+        &["sourceforge.net"]
+    }
+
+    // https://sourceforge.net/p/forge/documentation/Using%20the%20Release%20API/
+    fn latest<'a>(&'a self, query: &'a mut Query<'_>) -> BoxFuture<'a, Result<Found, CheckError>> {
+        Box::pin(async move {
+            let Some(project) = sourceforge_project(&query.url).map(String::from) else {
+                query.notes.push(format!(
+                    "No SourceForge project in URL for {}",
+                    query.distname
+                ));
+                return Ok(Found::Nothing);
+            };
+            let (client, config) = (query.client, query.config);
+            let best: SourceForgeBestRelease = get_json(
+                client.get(format!(
+                    "https://sourceforge.net/projects/{project}/best_release.json"
+                )),
+                &config.http,
+            )
+            .await?;
+            if let Some(release) = best.release {
+                // look at file name only, not at release folders
+                let file_name = release.filename.rsplit('/').next().unwrap_or_default();
+                match Version::from_str(file_name) {
+                    Ok(version) if query.filter().accepts(&version) => {
+                        return Ok(Found::Version(version.to_string()));
+                    }
+                    _ => (),
+                }
+            }
+            // no suitable best release; look at recently uploaded files
+            let rss = get_text(
+                client.get(format!("https://sourceforge.net/projects/{project}/rss")),
+                &config.http,
+            )
+            .await?;
+            match sourceforge_rss_version(&rss, &project, &query.filter(), &config.html.ignore) {
+                Some(version) => return Ok(Found::Version(version.to_string())),
+                None => query
+                    .notes
+                    .push(format!("No release files for {}", query.distname)),
+            }
+            Ok(Found::Nothing)
+        })
+    }
+}
+
+/// Project name from SourceForge URL, like `https://sourceforge.net/projects/zsh/` or
+/// `https://sourceforge.net/p/zsh/`.
+fn sourceforge_project(url: &Url) -> Option<&str> {
+    let mut segments = url.path_segments()?;
+    match segments.next()? {
+        "projects" | "p" => segments.next().filter(|project| !project.is_empty()),
+        _ => None,
+    }
+}
+
+/// Highest version among files listed in SourceForge project files RSS feed, whose items
+/// are titled with file paths like `/zsh/5.9/zsh-5.9.tar.xz`. If the project has a top
+/// folder named after it, files in other folders, like `/zsh-test/`, are skipped. Only file
+/// names are looked at, and files with `ignored` suffixes are skipped.
+fn sourceforge_rss_version(
+    rss: &str,
+    project: &str,
+    filter: &VersionFilter,
+    ignored: &[String],
+) -> Option<Version> {
+    let title = Regex::new(r"<title><!\[CDATA\[(/[^\]]*)\]\]></title>").expect("valid regex");
+    let paths: Vec<&str> = title
+        .captures_iter(rss)
+        .filter_map(|captures| captures.get(1))
+        .map(|path| path.as_str())
+        .collect();
+    let folder = format!("/{project}/");
+    let own_folder = paths.iter().any(|path| path.starts_with(&folder));
+    paths
+        .into_iter()
+        .filter(|path| !own_folder || path.starts_with(&folder))
+        .filter(|path| !is_ignored(path, ignored))
+        .filter_map(|path| Version::from_str(path.rsplit('/').next()?).ok())
+        .filter(|version| filter.accepts(version))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sourceforge_project() {
+        let project = |url| sourceforge_project(&Url::parse(url).unwrap()).map(String::from);
+        assert_eq!(
+            project("https://sourceforge.net/projects/zsh/").as_deref(),
+            Some("zsh")
+        );
+        assert_eq!(
+            project("https://sourceforge.net/p/zsh/code/").as_deref(),
+            Some("zsh")
+        );
+        assert_eq!(project("https://sourceforge.net/directory/"), None);
+    }
+
+    #[test]
+    fn test_sourceforge_rss_version() {
+        let rss = r#"<rss version="2.0"><channel>
+<title><![CDATA[Z shell]]></title>
+<item><title><![CDATA[/zsh/5.9/zsh-5.9.tar.xz.asc]]></title></item>
+<item><title><![CDATA[/zsh/5.9/zsh-5.9.tar.xz]]></title></item>
+<item><title><![CDATA[/zsh-test/5.9.0.2-test/zsh-5.9.0.2-test.tar.xz]]></title></item>
+<item><title><![CDATA[/zsh/5.8.1/zsh-5.8.1.tar.xz]]></title></item>
+<item><title><![CDATA[/zsh/5.10-rc1/zsh-5.10-rc1.tar.xz]]></title></item>
+</channel></rss>"#;
+        let ignored = crate::config::HtmlConfig::default().ignore;
+        let stable = VersionFilter {
+            stable_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            sourceforge_rss_version(rss, "zsh", &stable, &ignored),
+            Some(Version::new(vec![5, 9]))
+        );
+        let version =
+            sourceforge_rss_version(rss, "zsh", &VersionFilter::default(), &ignored).unwrap();
+        assert_eq!(version.to_string(), "5.10-rc1");
+        // no folder named after project
+        assert_eq!(
+            sourceforge_rss_version(rss, "zshell", &stable, &ignored),
+            Some(Version::new(vec![5, 9, 0, 2]))
+        );
+        assert_eq!(
+            sourceforge_rss_version("<rss/>", "zsh", &stable, &ignored),
+            None
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use crate::{error::ConfigError, version::IGNORED_SUFFIXES};
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::OsString,
+    fs::read,
+    path::{Path, PathBuf},
+};
+
+/// Settings read from the TOML configuration file.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// SQLite database file.
+    pub database: Option<String>,
+    pub check: CheckConfig,
+    pub github: GitHubConfig,
+    pub gitea: GiteaConfig,
+    pub gitlab: GitLabConfig,
+    pub html: HtmlConfig,
+    pub http: HttpConfig,
+    pub webhook: WebhookConfig,
+    /// Sources defined by user, by name, for sites needing their own extraction rules.
+    /// Built-in sources take precedence over ones with the same name.
+    pub sources: BTreeMap<String, CustomSource>,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct CheckConfig {
+    /// Packages checked within this time in seconds are skipped by `check` without package
+    /// name. Default is 2 hours.
+    pub interval: u64,
+    /// Number of packages checked at the same time. Default is 10; 0 is treated as 1.
+    pub concurrency: usize,
+    /// After successful check, follow permanent redirects (301 and 308) from master site, and
+    /// store the final URL as new master site.
+    pub follow_redirects: bool,
+}
+
+impl Default for CheckConfig {
+    fn default() -> Self {
+        Self {
+            interval: 7200,
+            concurrency: 10,
+            follow_redirects: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct GitHubConfig {
+    /// Personal access token, sent as bearer token.
+    pub token: Option<String>,
+    /// Look for the highest tag instead of the latest release.
+    pub prefer_tags: bool,
+    /// Longest time in seconds to wait for exhausted API rate limit to reset.
+    pub rate_limit_wait: u64,
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            prefer_tags: false,
+            rate_limit_wait: 60,
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct GiteaConfig {
+    /// Self-hosted Gitea or Forgejo instances, like `git.example.com`; packages added with
+    /// master site there use `gitea` source.
+    pub hosts: Vec<String>,
+    /// Access token, sent to codeberg.org and every instance in `hosts`.
+    pub token: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct GitLabConfig {
+    /// Self-hosted GitLab instances, like `gitlab.example.com`; packages added with master
+    /// site there use `gitlab` source.
+    pub hosts: Vec<String>,
+    /// Personal access token, sent to gitlab.com and every instance in `hosts`.
+    pub token: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct HtmlConfig {
+    /// Links to file names ending with these suffixes, or starting with them followed by
+    /// a separator, are not considered releases. Default is a list of checksum and signature
+    /// files, like `.sig` and `SHA256`.
+    pub ignore: Vec<String>,
+}
+
+impl Default for HtmlConfig {
+    fn default() -> Self {
+        Self {
+            ignore: IGNORED_SUFFIXES.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Number of retries after a connection error, timeout or 5xx/429 response.
+    pub retries: u32,
+    /// Delay before the first retry in milliseconds; doubled for every next retry.
+    pub retry_delay: u64,
+    /// Time limit for a whole request, from connecting until the response body is read,
+    /// in seconds.
+    pub timeout: u64,
+    /// Proxy URL for all requests. Overrides `HTTP_PROXY` and `HTTPS_PROXY` environment
+    /// variables; `NO_PROXY` still applies.
+    pub proxy: Option<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            retry_delay: 500,
+            timeout: 30,
+            proxy: None,
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    /// URL to POST check result to when new version is found.
+    pub url: Option<String>,
+}
+
+/// Source defined in configuration: document at URL made from template, and the way
+/// version is extracted from it.
+#[derive(Deserialize)]
+pub struct CustomSource {
+    /// URL of HTML page or JSON document; `{name}` is replaced with package name, and `{site}`
+    /// with master site or mirror.
+    pub url: String,
+    /// Regular expression with one capture group extracting version from page. Without it,
+    /// versions are looked for in links, as with `html` source.
+    pub pattern: Option<String>,
+    /// Path of version field in JSON document, as with `json` source.
+    pub json_path: Option<String>,
+    /// CSS selector of HTML page parts looked at for versions.
+    pub selector: Option<String>,
+}
+
+impl Config {
+    /// Read configuration from file, which must exist.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let data = read(path).map_err(|err| ConfigError::Read(path.to_path_buf(), err))?;
+        Ok(toml::from_str(&String::from_utf8_lossy(&data))?)
+    }
+}
+
+/// Candidate locations of a file: under XDG base directory `xdg_home`, under `home_default`
+/// relative to home directory, and `local` in current directory.
+fn search_path(
+    xdg_home: Option<OsString>,
+    home: Option<OsString>,
+    home_default: &str,
+    file: &str,
+    local: &str,
+) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    // relative XDG directories are invalid and should be ignored
+    if let Some(dir) = xdg_home.map(PathBuf::from).filter(|dir| dir.is_absolute()) {
+        paths.push(dir.join("vert").join(file));
+    }
+    if let Some(home) = home.filter(|home| !home.is_empty()) {
+        paths.push(Path::new(&home).join(home_default).join("vert").join(file));
+    }
+    paths.push(PathBuf::from(local));
+    paths
+}
+
+/// First existing file among candidates, or `None`.
+fn first_existing(paths: &[PathBuf]) -> Option<&PathBuf> {
+    paths.iter().find(|path| path.is_file())
+}
+
+/// Default configuration file: first existing of `$XDG_CONFIG_HOME/vert/config.toml`,
+/// `~/.config/vert/config.toml` and `vert.toml`. When there is none, `vert.toml`.
+#[must_use]
+pub fn default_config_path() -> PathBuf {
+    let paths = search_path(
+        env::var_os("XDG_CONFIG_HOME"),
+        env::var_os("HOME"),
+        ".config",
+        "config.toml",
+        "vert.toml",
+    );
+    first_existing(&paths)
+        .or(paths.last())
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Default database file: first existing of `$XDG_DATA_HOME/vert/vert.db`,
+/// `~/.local/share/vert/vert.db` and `vert.db`. When there is none, the first of these, so new
+/// database is created in data directory; its parent directories may need to be created.
+#[must_use]
+pub fn default_database_path() -> PathBuf {
+    let paths = search_path(
+        env::var_os("XDG_DATA_HOME"),
+        env::var_os("HOME"),
+        ".local/share",
+        "vert.db",
+        "vert.db",
+    );
+    first_existing(&paths)
+        .or(paths.first())
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config() {
+        let config: Config = toml::from_str(
+            r#"
+database = "/var/lib/vert/vert.db"
+
+[github]
+# no longer used, but still accepted
+account = "user"
+token = "secret"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.database.as_deref(), Some("/var/lib/vert/vert.db"));
+        assert_eq!(config.github.token.as_deref(), Some("secret"));
+        assert!(!config.github.prefer_tags);
+        assert_eq!(config.github.rate_limit_wait, 60);
+        assert!(config.gitea.hosts.is_empty());
+        assert!(config.gitea.token.is_none());
+        assert!(config.gitlab.hosts.is_empty());
+        assert!(config.gitlab.token.is_none());
+        assert!(config.html.ignore.iter().any(|suffix| suffix == ".sig"));
+        assert!(config.webhook.url.is_none());
+        assert_eq!(config.http.retries, 3);
+        assert_eq!(config.http.retry_delay, 500);
+        assert_eq!(config.http.timeout, 30);
+        assert!(config.http.proxy.is_none());
+        assert_eq!(config.check.interval, 7200);
+        assert_eq!(config.check.concurrency, 10);
+        assert!(!config.check.follow_redirects);
+        assert!(config.sources.is_empty());
+    }
+
+    #[test]
+    fn test_custom_sources() {
+        let config: Config = toml::from_str(
+            r#"
+[sources.intranet]
+url = "https://downloads.example.com/{name}/"
+pattern = 'product-([\d.]+)\.zip'
+selector = '#downloads'
+
+[sources.releases]
+url = "{site}/latest.json"
+json_path = "$.version"
+"#,
+        )
+        .unwrap();
+        let intranet = &config.sources["intranet"];
+        assert_eq!(intranet.url, "https://downloads.example.com/{name}/");
+        assert_eq!(intranet.pattern.as_deref(), Some(r"product-([\d.]+)\.zip"));
+        assert!(intranet.json_path.is_none());
+        assert_eq!(intranet.selector.as_deref(), Some("#downloads"));
+        let releases = &config.sources["releases"];
+        assert!(releases.pattern.is_none());
+        assert_eq!(releases.json_path.as_deref(), Some("$.version"));
+    }
+
+    #[test]
+    fn test_search_path() {
+        assert_eq!(
+            search_path(
+                Some("/xdg".into()),
+                Some("/home/user".into()),
+                ".config",
+                "config.toml",
+                "vert.toml"
+            ),
+            [
+                PathBuf::from("/xdg/vert/config.toml"),
+                PathBuf::from("/home/user/.config/vert/config.toml"),
+                PathBuf::from("vert.toml"),
+            ]
+        );
+        assert_eq!(
+            search_path(
+                Some("relative".into()),
+                None,
+                ".local/share",
+                "vert.db",
+                "vert.db"
+            ),
+            [PathBuf::from("vert.db")]
+        );
+        assert_eq!(
+            search_path(
+                None,
+                Some("/root".into()),
+                ".local/share",
+                "vert.db",
+                "vert.db"
+            ),
+            [
+                PathBuf::from("/root/.local/share/vert/vert.db"),
+                PathBuf::from("vert.db"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_existing() {
+        let paths = [
+            PathBuf::from("/nonexistent/vert.toml"),
+            PathBuf::from("Cargo.toml"),
+            PathBuf::from("src"),
+        ];
+        assert_eq!(first_existing(&paths), Some(&paths[1]));
+        assert_eq!(first_existing(&paths[2..]), None);
+    }
+
+    #[test]
+    fn test_from_file() {
+        assert!(matches!(
+            Config::from_file(Path::new("/nonexistent/vert.toml")),
+            Err(ConfigError::Read(..))
+        ));
+        assert!(Config::from_file(Path::new("Cargo.toml")).is_ok());
+    }
+}
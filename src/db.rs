@@ -0,0 +1,118 @@
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool},
+    Error as SqlxError,
+};
+use std::{str::FromStr, time::Duration};
+
+/// How long a write waits for other connections to release the database lock before
+/// failing with `SQLITE_BUSY`.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Database schema migrations from `migrations` directory.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Open database file, creating it if missing, and bring the schema up to date.
+/// Write-ahead log lets concurrent checks read while one of them writes, and writers wait
+/// for each other instead of failing.
+pub async fn connect(path: &str) -> Result<SqlitePool, SqlxError> {
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{path}"))?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(BUSY_TIMEOUT);
+    let pool = SqlitePool::connect_with(options).await?;
+    MIGRATOR.run(&pool).await?;
+    Ok(pool)
+}
+
+/// In-memory database for tests.
+#[cfg(test)]
+pub(crate) async fn memory() -> SqlitePool {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        // every connection would have its own in-memory database
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    MIGRATOR.run(&pool).await.unwrap();
+    pool
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::Package;
+    use futures::future::try_join_all;
+    use sqlx::query_scalar;
+    use std::{env::temp_dir, fs::remove_file, process};
+
+    #[tokio::test]
+    async fn test_migrate_twice() {
+        let pool = memory().await;
+        MIGRATOR.run(&pool).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_migrate_existing() {
+        // database created before migrations were introduced
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE package (id INTEGER PRIMARY KEY, distname TEXT, master_site TEXT, \
+            version TEXT, local_version TEXT, last_check DATETIME NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        // names were not unique
+        for _ in 0..2 {
+            sqlx::query(
+                "INSERT INTO package (distname, master_site, version, local_version, last_check) \
+                VALUES ('sudo', 'https://www.sudo.ws/dist/', '1.9.14', '1.9.14', '2023-08-03T12:00:00Z')",
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        MIGRATOR.run(&pool).await.unwrap();
+
+        let names: Vec<String> = query_scalar("SELECT distname FROM package ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(names, ["sudo", "sudo-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_writes() {
+        let path = temp_dir().join(format!("vert-test-{}.db", process::id()));
+        let path = path.to_str().unwrap();
+        let pool = connect(path).await.unwrap();
+        let mut pkgs = Vec::new();
+        for i in 0..50 {
+            let pkg = Package::add(
+                &pool,
+                format!("pkg{i}"),
+                format!("https://example.com/pkg{i}/"),
+                "1.0".into(),
+                None,
+                "html",
+            )
+            .await
+            .unwrap();
+            pkgs.push(pkg);
+        }
+
+        // every write gets its own connection from the pool
+        let result = try_join_all(pkgs.iter_mut().map(|pkg| pkg.update_last_check(&pool))).await;
+        pool.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = remove_file(format!("{path}{suffix}"));
+        }
+        result.unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+use reqwest::StatusCode;
+use sqlx::{types::time::OffsetDateTime, Error as SqlxError};
+use std::{fmt, io, path::PathBuf};
+
+/// Failure to check upstream version of a package.
+#[derive(Debug)]
+pub enum CheckError {
+    /// FTP connection failed, or server refused a command.
+    Ftp(String),
+    Http(reqwest::Error),
+    Json(serde_json::Error),
+    /// Invalid version pattern.
+    Pattern(regex::Error),
+    /// API rate limit exhausted until given time (seconds since Unix epoch), and how to
+    /// raise the limit, if known.
+    RateLimited(u64, Option<&'static str>),
+    /// Unsupported CSS selector.
+    Selector(String),
+    /// Unknown package source name.
+    Source(String),
+    Status(StatusCode),
+    Url(url::ParseError),
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ftp(err) => write!(f, "FTP error: {err}"),
+            Self::Http(err) => write!(f, "HTTP error: {err}"),
+            Self::Json(err) => write!(f, "JSON error: {err}"),
+            Self::Pattern(err) => write!(f, "invalid pattern: {err}"),
+            Self::RateLimited(reset, hint) => {
+                write!(f, "API rate limit exceeded")?;
+                if let Some(reset) = i64::try_from(*reset)
+                    .ok()
+                    .and_then(|reset| OffsetDateTime::from_unix_timestamp(reset).ok())
+                {
+                    write!(f, " until {reset}")?;
+                }
+                if let Some(hint) = hint {
+                    write!(f, "; {hint}")?;
+                }
+                Ok(())
+            }
+            Self::Selector(err) | Self::Source(err) => write!(f, "{err}"),
+            Self::Status(status) => write!(f, "status {status}"),
+            Self::Url(err) => write!(f, "invalid URL: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for CheckError {}
+
+impl From<reqwest::Error> for CheckError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Http(err)
+    }
+}
+
+impl From<serde_json::Error> for CheckError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<regex::Error> for CheckError {
+    fn from(err: regex::Error) -> Self {
+        Self::Pattern(err)
+    }
+}
+
+impl From<url::ParseError> for CheckError {
+    fn from(err: url::ParseError) -> Self {
+        Self::Url(err)
+    }
+}
+
+/// Failure to find package by name.
+#[derive(Debug)]
+pub enum FindError {
+    /// Name matches more than one package; their names.
+    Ambiguous(String, Vec<String>),
+    Db(SqlxError),
+    NotFound(String),
+}
+
+impl fmt::Display for FindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ambiguous(name, matches) => {
+                write!(f, "Package '{name}' is ambiguous: {}", matches.join(", "))
+            }
+            Self::Db(err) => write!(f, "database error: {err}"),
+            Self::NotFound(name) => write!(f, "Package '{name}' not found"),
+        }
+    }
+}
+
+impl std::error::Error for FindError {}
+
+impl From<SqlxError> for FindError {
+    fn from(err: SqlxError) -> Self {
+        Self::Db(err)
+    }
+}
+
+/// Failure to load configuration file.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// File could not be read; its path.
+    Read(PathBuf, io::Error),
+    Toml(toml::de::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(path, err) => write!(f, "cannot read {}: {err}", path.display()),
+            Self::Toml(err) => write!(f, "invalid configuration: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        Self::Toml(err)
+    }
+}
+
+/// Failure to parse version string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseVersionError {
+    /// No digit in the string.
+    NoNumber,
+    /// Single number is part of a word, like `SHA256`.
+    Glued,
+    /// Number not starting version components, or not in semantic version syntax.
+    Malformed,
+}
+
+impl fmt::Display for ParseVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoNumber => write!(f, "no number in version"),
+            Self::Glued => write!(f, "number is part of a word"),
+            Self::Malformed => write!(f, "malformed version"),
+        }
+    }
+}
+
+impl std::error::Error for ParseVersionError {}
@@ -0,0 +1,89 @@
+use crate::package::PackageChange;
+use sqlx::types::time::OffsetDateTime;
+use std::fmt::Write;
+use time::format_description::well_known::Rfc3339;
+
+/// Replace characters with special meaning in XML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn timestamp(time: OffsetDateTime) -> String {
+    time.format(&Rfc3339).unwrap_or_default()
+}
+
+/// Atom feed with an entry for every version change, newest first. Feed is as recent as its
+/// newest entry, or `now` when there are none.
+#[must_use]
+pub fn atom(changes: &[PackageChange], now: OffsetDateTime) -> String {
+    let updated = changes.iter().map(|change| change.observed_at).max();
+    let mut feed = String::new();
+    feed.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    feed.push_str("  <id>urn:vert:changes</id>\n");
+    feed.push_str("  <title>Version changes</title>\n");
+    feed.push_str("  <author><name>vert</name></author>\n");
+    // writing to String doesn't fail
+    let _ = writeln!(
+        feed,
+        "  <updated>{}</updated>",
+        timestamp(updated.unwrap_or(now))
+    );
+    for change in changes {
+        let distname = escape(&change.distname);
+        let new_version = escape(&change.new_version);
+        let _ = write!(
+            feed,
+            "  <entry>\n    <id>urn:vert:{distname}:{new_version}</id>\n    \
+            <title>{distname}</title>\n    <link href=\"{}\"/>\n    \
+            <updated>{}</updated>\n    <summary>{} -&gt; {new_version}</summary>\n  </entry>\n",
+            escape(&change.master_site),
+            timestamp(change.observed_at),
+            escape(&change.old_version),
+        );
+    }
+    feed.push_str("</feed>\n");
+    feed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a<b> & \"c\""), "a&lt;b&gt; &amp; &quot;c&quot;");
+    }
+
+    #[test]
+    fn test_atom() {
+        let observed_at = OffsetDateTime::from_unix_timestamp(1_691_064_000).unwrap();
+        let changes = [PackageChange {
+            distname: "sudo".into(),
+            master_site: "https://www.sudo.ws/dist/?a=1&b=2".into(),
+            old_version: "1.9.13".into(),
+            new_version: "1.9.14".into(),
+            observed_at,
+        }];
+        let feed = atom(&changes, OffsetDateTime::now_utc());
+        assert!(feed.contains("<updated>2023-08-03T12:00:00Z</updated>\n  <entry>"));
+        assert!(feed.contains("<title>sudo</title>"));
+        assert!(feed.contains("<link href=\"https://www.sudo.ws/dist/?a=1&amp;b=2\"/>"));
+        assert!(feed.contains("<summary>1.9.13 -&gt; 1.9.14</summary>"));
+        assert!(feed.ends_with("</feed>\n"));
+
+        let now = OffsetDateTime::from_unix_timestamp(0).unwrap();
+        assert!(atom(&[], now).contains("<updated>1970-01-01T00:00:00Z</updated>"));
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{config::HttpConfig, error::CheckError};
+use reqwest::{
+    header, redirect::Policy, Client, ClientBuilder, NoProxy, Proxy, RequestBuilder, Response,
+    StatusCode,
+};
+use serde::de::DeserializeOwned;
+use std::time::{Duration, SystemTime};
+
+/// Build HTTP client with default headers. Proxy is taken from configuration, or from
+/// environment variables. Build it once and share it by all checks, so connections are
+/// reused.
+pub fn http_client(config: &HttpConfig) -> Result<Client, CheckError> {
+    Ok(client_builder(config)?.build()?)
+}
+
+/// Build HTTP client which doesn't follow redirects, to find where master sites moved. Like
+/// `http_client`, build it once and share it by all checks.
+pub fn redirect_client(config: &HttpConfig) -> Result<Client, CheckError> {
+    Ok(client_builder(config)?.redirect(Policy::none()).build()?)
+}
+
+/// Common settings of HTTP clients.
+fn client_builder(config: &HttpConfig) -> Result<ClientBuilder, CheckError> {
+    let mut headers = header::HeaderMap::new();
+    headers.insert(
+        header::ACCEPT,
+        header::HeaderValue::from_static("application/json"),
+    );
+    headers.insert(
+        header::USER_AGENT,
+        header::HeaderValue::from_static("Version-Tracker"),
+    );
+    let mut builder = Client::builder()
+        .default_headers(headers)
+        .timeout(Duration::from_secs(config.timeout));
+    if let Some(proxy) = &config.proxy {
+        builder = builder.proxy(Proxy::all(proxy)?.no_proxy(NoProxy::from_env()));
+    }
+    Ok(builder)
+}
+
+/// Delay before retry number `attempt` (counting from 0).
+fn backoff(base_delay: u64, attempt: u32) -> Duration {
+    Duration::from_millis(base_delay.saturating_mul(1 << attempt.min(16)))
+}
+
+/// Send request, retrying on connection errors and on 5xx and 429 responses.
+pub(crate) async fn send(
+    request: RequestBuilder,
+    http: &HttpConfig,
+) -> Result<Response, CheckError> {
+    let mut attempt = 0;
+    loop {
+        let Some(retry_request) = request.try_clone() else {
+            // streaming body can't be resent
+            return Ok(request.send().await?);
+        };
+        let result = retry_request.send().await;
+        let transient = match &result {
+            Ok(response) => {
+                (response.status().is_server_error()
+                    || response.status() == StatusCode::TOO_MANY_REQUESTS)
+                    && rate_limit_reset(response).is_none()
+            }
+            Err(err) => err.is_connect() || err.is_timeout(),
+        };
+        if !transient || attempt >= http.retries {
+            return Ok(result?);
+        }
+        let delay = backoff(http.retry_delay, attempt);
+        // up to 50% jitter, so concurrent checks don't retry in lockstep
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .subsec_nanos();
+        let jitter = delay.mul_f64(f64::from(nanos % 500) / 1000.0);
+        tokio::time::sleep(delay + jitter).await;
+        attempt += 1;
+    }
+}
+
+/// Seconds since Unix epoch.
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// If response says the API rate limit is exhausted, return its reset time.
+fn rate_limit_reset(response: &Response) -> Option<u64> {
+    let status = response.status();
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let headers = response.headers();
+    if headers.get("x-ratelimit-remaining")?.to_str().ok()? != "0" {
+        return None;
+    }
+    headers
+        .get("x-ratelimit-reset")?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Fail on unsuccessful response status.
+pub(crate) fn check_status(response: Response) -> Result<Response, CheckError> {
+    if response.status() != StatusCode::OK {
+        if let Some(reset) = rate_limit_reset(&response) {
+            return Err(CheckError::RateLimited(reset, None));
+        }
+        return Err(CheckError::Status(response.status()));
+    }
+    Ok(response)
+}
+
+/// Send request and return response body, failing on unsuccessful status.
+pub(crate) async fn get_text(
+    request: RequestBuilder,
+    http: &HttpConfig,
+) -> Result<String, CheckError> {
+    let response = check_status(send(request, http).await?)?;
+    Ok(response.text().await?)
+}
+
+/// Send request and decode JSON response.
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    request: RequestBuilder,
+    http: &HttpConfig,
+) -> Result<T, CheckError> {
+    let body = get_text(request, http).await?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(500, 0), Duration::from_millis(500));
+        assert_eq!(backoff(500, 1), Duration::from_millis(1000));
+        assert_eq!(backoff(500, 3), Duration::from_millis(4000));
+    }
+}
@@ -1,5 +1,12 @@
 #[macro_use]
 extern crate serde;
 
+pub mod backend;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod feed;
+pub mod http;
 pub mod package;
+pub mod source;
 pub mod version;
@@ -1,23 +1,338 @@
-use std::{fs::read, path::PathBuf};
+use std::{
+    env,
+    fs::{create_dir_all, read_to_string, write},
+    io::{stdout, IsTerminal},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+    time::Duration,
+};
 
-use clap::{arg, command, value_parser, Command};
-use sqlx::sqlite::SqlitePool;
-use toml::{Table, Value};
-use vert::package::Package;
+use clap::{arg, command, value_parser, Arg, ArgAction, Command};
+use futures::{future::ready, stream::StreamExt};
+use regex::Regex;
+use reqwest::Url;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use vert::{
+    config::{default_config_path, default_database_path, Config},
+    db,
+    error::FindError,
+    feed,
+    http::{http_client, redirect_client},
+    package::{
+        CheckResult, CheckSummary, MarkOutcome, Package, PackageList, PackageSpec, UnmarkOutcome,
+    },
+    source::Source,
+    version::{parse_release, Selector},
+};
+
+/// Parse package spec line: `distname url version`.
+fn parse_spec_line(line: &str) -> Result<PackageSpec, String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if let [distname, master_site, version] = fields[..] {
+        Ok(PackageSpec {
+            distname: distname.into(),
+            master_site: master_site.into(),
+            version: version.into(),
+            local_version: Some(version.into()),
+            source: None,
+            stable_only: false,
+            held: false,
+            scan_text: false,
+            pattern: None,
+            json_path: None,
+            mirrors: Vec::new(),
+            all_releases: false,
+            selector: None,
+            descend: false,
+            semver: false,
+            ignore: None,
+            pinned: None,
+            tag_prefix: None,
+            tag_suffix: None,
+            epoch: None,
+        })
+    } else {
+        Err(format!(
+            "expected `distname url version`, got {} fields",
+            fields.len()
+        ))
+    }
+}
+
+/// Guess package source from master site, taking configured self-hosted instances into account.
+fn detect_source(master_site: &str, config: &Config) -> Source {
+    // git repository on any host
+    let url = Url::parse(master_site)
+        .ok()
+        .filter(|url| !url.scheme().starts_with("git+"));
+    match url.as_ref().and_then(Url::host_str) {
+        Some(host) if config.gitea.hosts.iter().any(|gitea| gitea == host) => Source::Gitea,
+        Some(host) if config.gitlab.hosts.iter().any(|gitlab| gitlab == host) => Source::GitLab,
+        _ => Source::detect(master_site),
+    }
+}
+
+/// Check that source is built in or defined in configuration.
+fn parse_source(name: &str, config: &Config) -> Result<String, String> {
+    if Source::from_str(name).is_ok() || config.sources.contains_key(name) {
+        Ok(name.into())
+    } else {
+        Err(format!("unknown source {name}"))
+    }
+}
+
+/// Check that pattern of ignored versions is a valid regular expression.
+fn parse_ignore(ignore: &str) -> Result<String, String> {
+    Regex::new(ignore).map_err(|err| err.to_string())?;
+    Ok(ignore.into())
+}
+
+/// Check that CSS selector is supported.
+fn parse_selector(selector: &str) -> Result<String, String> {
+    Selector::from_str(selector)?;
+    Ok(selector.into())
+}
+
+/// Check that version pattern is a valid regular expression with a capture group.
+fn parse_pattern(pattern: &str) -> Result<String, String> {
+    let regex = Regex::new(pattern).map_err(|err| err.to_string())?;
+    if regex.captures_len() < 2 {
+        return Err("pattern needs a capture group for the version".into());
+    }
+    Ok(pattern.into())
+}
+
+/// Check that version given on command line can be compared.
+fn parse_release_arg(release: &str) -> Result<String, String> {
+    parse_release(release)?;
+    Ok(release.into())
+}
+
+/// Check that package spec has valid URL and version.
+fn validate_spec(spec: &PackageSpec) -> Result<(), String> {
+    Url::parse(&spec.master_site)
+        .map_err(|err| format!("invalid URL {}: {err}", spec.master_site))?;
+    parse_release(&spec.version)?;
+    Ok(())
+}
+
+/// Package spec, or reason it is invalid, with its location in the file.
+type SpecEntry = (String, Result<PackageSpec, String>);
+
+/// Read package specs from file, either TOML package list or one spec per line.
+fn read_specs(path: &Path) -> Result<Vec<SpecEntry>, Box<dyn std::error::Error>> {
+    let data = read_to_string(path)?;
+    let specs = if path.extension().is_some_and(|ext| ext == "toml") {
+        let list: PackageList = toml::from_str(&data)?;
+        list.package
+            .into_iter()
+            .enumerate()
+            .map(|(index, spec)| (format!("{} entry {}", path.display(), index + 1), Ok(spec)))
+            .collect()
+    } else {
+        data.lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line = line.trim();
+                !line.is_empty() && !line.starts_with('#')
+            })
+            .map(|(index, line)| {
+                (
+                    format!("{}:{}", path.display(), index + 1),
+                    parse_spec_line(line),
+                )
+            })
+            .collect()
+    };
+    Ok(specs)
+}
+
+/// ANSI colors of package state.
+const GREEN: &str = "32";
+const YELLOW: &str = "33";
+const RED: &str = "31";
+
+/// Decide whether to color output: `always`, `never`, or `auto` for terminal unless
+/// `NO_COLOR` is set.
+fn use_color(when: &str) -> bool {
+    match when {
+        "always" => true,
+        "never" => false,
+        _ => stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+    }
+}
+
+/// Wrap text in ANSI color escape sequence if `color` is set.
+fn paint(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("\x1b[{code}m{text}\x1b[0m")
+    } else {
+        text.into()
+    }
+}
+
+/// Print packages as a table with aligned columns: outdated in yellow, failed in red,
+/// up to date in green.
+fn print_table(pkgs: &[Package], color: bool) {
+    let infos: Vec<_> = pkgs.iter().map(Package::info).collect();
+    let name_width = infos
+        .iter()
+        .map(|info| info.distname.len())
+        .max()
+        .unwrap_or(0);
+    let local_width = infos
+        .iter()
+        .map(|info| info.local_version.unwrap_or("-").len())
+        .max()
+        .unwrap_or(0);
+    for info in &infos {
+        let code = if info.last_error.is_some() {
+            RED
+        } else if info.outdated {
+            YELLOW
+        } else {
+            GREEN
+        };
+        // pad before painting, escape sequences have no width
+        println!(
+            "{}  {:local_width$} -> {}{}",
+            paint(&format!("{:name_width$}", info.distname), code, color),
+            info.local_version.unwrap_or("-"),
+            paint(info.version, code, color),
+            match (info.held, info.pinned) {
+                (true, _) => " [held]".into(),
+                (false, Some(pinned)) => format!(" [pinned at {pinned}]"),
+                (false, None) => String::new(),
+            }
+        );
+    }
+}
+
+/// Print packages with error of their last check.
+fn print_errors(pkgs: &[Package]) {
+    for info in pkgs.iter().map(Package::info) {
+        println!(
+            "{} [{}] {}: {}",
+            info.distname,
+            info.master_site,
+            info.last_error_at.as_deref().unwrap_or("-"),
+            info.last_error.unwrap_or("-")
+        );
+    }
+}
+
+/// Print check result in human-readable form.
+fn print_check_result(result: &CheckResult) {
+    print_notes(result);
+    if result.updated {
+        println!(
+            "{} {} -> {}",
+            result.distname,
+            result.local_version.as_deref().unwrap_or("-"),
+            result.version
+        );
+    }
+    if let Some(err) = &result.error {
+        eprintln!(
+            "Error checking {} [{}]: {}",
+            result.distname, result.master_site, err
+        );
+    }
+}
+
+/// Print remarks about a check to stderr.
+fn print_notes(result: &CheckResult) {
+    for note in &result.notes {
+        eprintln!("{note}");
+    }
+}
+
+/// Print check summary, noting dry run.
+fn print_summary(summary: &CheckSummary, dry_run: bool) {
+    if dry_run {
+        println!("{summary} (dry run, no changes written)");
+    } else {
+        println!("{summary}");
+    }
+}
+
+/// `--notify` flag of `check`, if built with desktop notifications.
+fn notify_arg() -> Option<Arg> {
+    cfg!(feature = "notify")
+        .then(|| arg!(--notify "show desktop notification about every new version"))
+}
+
+/// Show desktop notification about new version. Failure is only reported.
+#[cfg(feature = "notify")]
+fn notify(result: &CheckResult) {
+    if let Err(err) = notify_rust::Notification::new()
+        .appname("vert")
+        .summary(&result.distname)
+        .body(&format!("{} -> {}", result.old_version, result.version))
+        .show()
+    {
+        eprintln!("Notification error for {}: {}", result.distname, err);
+    }
+}
+
+#[cfg(not(feature = "notify"))]
+fn notify(_result: &CheckResult) {}
+
+/// Exit status when outdated packages exist and `--exit-code` is given.
+const EXIT_OUTDATED: u8 = 1;
+/// Exit status on failure.
+const EXIT_FAILURE: u8 = 2;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::from(EXIT_FAILURE)
+        }
+    }
+}
+
+async fn run() -> Result<ExitCode, Box<dyn std::error::Error>> {
     let matches = command!()
         .arg(
             arg!(-c --config <FILE> "configuration file")
                 .required(false)
-                .value_parser(value_parser!(PathBuf))
-                .default_value("vert.toml"),
+                .value_parser(value_parser!(PathBuf)),
         )
         .arg(
             arg!(-d --db <FILE> "SQLite database file")
                 .required(false)
-                .default_value("vert.db"),
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(arg!(-v --verbose "print more information"))
+        .arg(
+            arg!(--"exit-code" "exit with status 1 if outdated packages exist, 2 on error")
+                .global(true),
+        )
+        .arg(
+            arg!(--exact "match package name exactly, not by unambiguous prefix or substring")
+                .global(true),
+        )
+        .arg(
+            arg!(--"dry-run" "check for new versions without storing anything in the database")
+                .global(true),
+        )
+        .arg(
+            arg!(--color <WHEN> "color output")
+                .required(false)
+                .global(true)
+                .value_parser(["auto", "always", "never"])
+                .default_value("auto"),
+        )
+        .arg(
+            arg!(--format <FORMAT> "output format")
+                .required(false)
+                .global(true)
+                .value_parser(["text", "json"])
+                .default_value("text"),
         )
         .arg_required_else_help(true)
         .propagate_version(true)
@@ -25,145 +340,918 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .subcommand(
             Command::new("add")
                 .about("Add package")
-                .arg(arg!(-l --url <URL> "package master site").required(true))
-                .arg(arg!(-r --release <VERSION> "locally installed version").required(true))
-                .arg(arg!(<pkg> "package name")),
+                .arg(
+                    arg!(-F --from <FILE> "add packages from file, one `distname url version` per line")
+                        .required(false)
+                        .value_parser(value_parser!(PathBuf))
+                        .conflicts_with_all(["url", "release", "pkg"]),
+                )
+                .arg(arg!(-l --url <URL> "package master site").required_unless_present("from"))
+                .arg(
+                    arg!(-r --release <VERSION> "locally installed version")
+                        .required_unless_present_any(["from", "auto"])
+                        .value_parser(parse_release_arg),
+                )
+                .arg(
+                    arg!(--auto "take the current version found at master site as locally installed")
+                        .conflicts_with_all(["from", "release"]),
+                )
+                .arg(
+                    arg!(-i --interval <SECONDS> "time between checks")
+                        .required(false)
+                        .value_parser(value_parser!(i64)),
+                )
+                .arg(
+                    arg!(-s --source <SOURCE> "where to look for new versions, built-in or defined in configuration [default: detected from URL]")
+                        .required(false),
+                )
+                .arg(arg!(--"stable-only" "ignore pre-release versions"))
+                .arg(arg!(--semver "compare versions as semantic versions, like 1.0.0-rc.1 < 1.0.0"))
+                .arg(
+                    arg!(--ignore <REGEX> "regular expression of versions to skip, like `-beta|nightly`")
+                        .required(false)
+                        .value_parser(parse_ignore),
+                )
+                .arg(arg!(--"tag-prefix" <PREFIX> "prefix of release tags, stripped to get version, like `jq-`").required(false))
+                .arg(arg!(--"tag-suffix" <SUFFIX> "suffix of release tags, stripped to get version, like `-final`").required(false))
+                .arg(
+                    arg!(--epoch <EPOCH> "epoch of versions found upstream, for a scheme starting over, like `2023.1` followed by `1.0`")
+                        .required(false)
+                        .value_parser(value_parser!(u16)),
+                )
+                .arg(
+                    arg!(-p --pattern <REGEX> "regular expression extracting version from HTML page or repository tag")
+                        .required(false)
+                        .value_parser(parse_pattern),
+                )
+                .arg(
+                    arg!(--"json-path" <PATH> "path of version field in JSON document, like `$.latest.version`")
+                        .required_if_eq("source", "json"),
+                )
+                .arg(arg!(--"scan-text" "look for versions in text, option values and data-version attributes of HTML page, not only in links"))
+                .arg(
+                    arg!(--selector <SELECTOR> "CSS selector of HTML page parts to look at, like `#stable a`")
+                        .required(false)
+                        .value_parser(parse_selector),
+                )
+                .arg(arg!(--descend "look for versions in the highest version subdirectory of HTML directory listing, like `1.24/`"))
+                .arg(arg!(--"all-releases" "look at all GitHub releases, including pre-releases, not only the latest one"))
+                .arg(
+                    arg!(-m --mirror <URL> "fallback master site, tried when master site fails; may be repeated")
+                        .required(false)
+                        .action(ArgAction::Append),
+                )
+                .arg(arg!([pkg] "package name").required_unless_present("from")),
         )
         .subcommand(
             Command::new("check")
                 .about("Check for new version")
-                .arg(arg!([pkg] "package name")),
+                .arg(
+                    arg!(-f --force "check all packages, regardless of last check")
+                        .alias("all")
+                        .conflicts_with("max-age"),
+                )
+                .arg(
+                    arg!(-m --"max-age" <SECONDS> "skip packages checked within this time [default: check.interval or 7200]")
+                        .required(false)
+                        .value_parser(value_parser!(u64)),
+                )
+                .arg(arg!(--"no-webhook" "don't notify webhook about new versions"))
+                .arg(arg!(--"follow-redirects" "store master site moved with a permanent redirect"))
+                .arg(
+                    arg!(-j --concurrency <N> "packages checked at the same time [default: check.concurrency or 10]")
+                        .required(false)
+                        .value_parser(value_parser!(usize)),
+                )
+                .args(notify_arg())
+                .arg(arg!([pkg]... "package names [default: all packages due for check]")),
         )
         .subcommand(
             Command::new("delete")
                 .about("Delete package")
                 .arg(arg!(<pkg> "package name")),
         )
+        .subcommand(
+            Command::new("export")
+                .about("Export package list as TOML, or JSON with --format json")
+                .arg(
+                    arg!(-o --output <FILE> "output file instead of standard output")
+                        .required(false)
+                        .value_parser(value_parser!(PathBuf)),
+                ),
+        )
+        .subcommand(
+            Command::new("feed")
+                .about("Write Atom feed of recent version changes")
+                .arg(
+                    arg!(-o --output <FILE> "output file instead of standard output")
+                        .required(false)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(-n --limit <N> "number of entries")
+                        .required(false)
+                        .value_parser(value_parser!(i64))
+                        .default_value("50"),
+                ),
+        )
+        .subcommand(
+            Command::new("history")
+                .about("Display version history of package")
+                .arg(arg!(<pkg> "package name")),
+        )
+        .subcommand(
+            Command::new("hold")
+                .about("Hold package at local version; it is not checked nor reported as outdated")
+                .arg(arg!(<pkg> "package name")),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Import package list from TOML or JSON file")
+                .arg(arg!(--overwrite "update packages that already exist"))
+                .arg(arg!(<file> "package list file").value_parser(value_parser!(PathBuf))),
+        )
         .subcommand(
             Command::new("info")
                 .about("Display information about package")
+                .arg(arg!(-e --errors "packages whose last check failed").conflicts_with("pkg"))
                 .arg(arg!([pkg] "package name")),
         )
+        .subcommand(
+            Command::new("list")
+                .about("List all packages")
+                .arg(arg!(-o --outdated "only packages with newer version"))
+                .arg(arg!(-e --errors "only packages whose last check failed"))
+                .arg(
+                    arg!(-s --sort <ORDER> "sort order")
+                        .required(false)
+                        .value_parser(["name", "version"])
+                        .default_value("name"),
+                ),
+        )
         .subcommand(
             Command::new("mark")
                 .about("Mark as updated")
                 .arg(arg!(<pkg> "package name")),
         )
+        .subcommand(
+            Command::new("pin")
+                .about("Pin package at version, local one by default; it is checked, but reported as outdated only below that version")
+                .arg(arg!(<pkg> "package name"))
+                .arg(arg!([version] "version to stay at").value_parser(parse_release_arg)),
+        )
+        .subcommand(
+            Command::new("rename")
+                .about("Rename package, keeping its history")
+                .arg(arg!(<pkg> "package name"))
+                .arg(arg!(<name> "new package name")),
+        )
+        .subcommand(
+            Command::new("search")
+                .about("Find packages by part of their name")
+                .arg(arg!(--"master-site" "match master site too"))
+                .arg(arg!(<pattern> "part of package name, in any case")),
+        )
+        .subcommand(Command::new("stats").about("Count packages and outdated ones by source"))
+        .subcommand(
+            Command::new("unhold")
+                .about("Release hold of package")
+                .arg(arg!(<pkg> "package name")),
+        )
+        .subcommand(
+            Command::new("unpin")
+                .about("Remove pin of package, so it is compared with upstream version again")
+                .arg(arg!(<pkg> "package name")),
+        )
+        .subcommand(
+            Command::new("unmark")
+                .about("Revert the last mark, restoring previous local version")
+                .arg(arg!(<pkg> "package name")),
+        )
         .subcommand(
             Command::new("update")
                 .about("Update package")
                 .arg(arg!(-l --url [URL] "package master site"))
                 .arg(arg!(-n --name [NAME] "new package name"))
-                .arg(arg!(-r --release [VERSION] "locally installed version"))
+                .arg(
+                    arg!(-r --release [VERSION] "locally installed version")
+                        .value_parser(parse_release_arg),
+                )
+                .arg(
+                    arg!(-i --interval [SECONDS] "time between checks")
+                        .value_parser(value_parser!(i64)),
+                )
+                .arg(
+                    arg!(-s --source [SOURCE] "where to look for new versions, built-in or defined in configuration"),
+                )
+                .arg(arg!(--"stable-only" "ignore pre-release versions"))
+                .arg(
+                    arg!(--prerelease "consider pre-release versions")
+                        .conflicts_with("stable-only"),
+                )
+                .arg(arg!(--semver "compare versions as semantic versions, like 1.0.0-rc.1 < 1.0.0"))
+                .arg(
+                    arg!(--"no-semver" "compare versions by their numbers and pre-release markers")
+                        .conflicts_with("semver"),
+                )
+                .arg(
+                    arg!(--ignore [REGEX] "regular expression of versions to skip, like `-beta|nightly`")
+                        .value_parser(parse_ignore),
+                )
+                .arg(
+                    arg!(--"no-ignore" "skip no versions")
+                        .conflicts_with("ignore"),
+                )
+                .arg(arg!(--"tag-prefix" [PREFIX] "prefix of release tags, stripped to get version, like `jq-`"))
+                .arg(
+                    arg!(--"no-tag-prefix" "take version from release tags after the first number")
+                        .conflicts_with("tag-prefix"),
+                )
+                .arg(arg!(--"tag-suffix" [SUFFIX] "suffix of release tags, stripped to get version, like `-final`"))
+                .arg(
+                    arg!(--"no-tag-suffix" "take whole release tags after prefix as versions")
+                        .conflicts_with("tag-suffix"),
+                )
+                .arg(
+                    arg!(--epoch [EPOCH] "epoch of versions found upstream, for a scheme starting over, like `2023.1` followed by `1.0`")
+                        .value_parser(value_parser!(u16)),
+                )
+                .arg(
+                    arg!(--"no-epoch" "take versions found upstream as they are")
+                        .conflicts_with("epoch"),
+                )
+                .arg(
+                    arg!(-p --pattern [REGEX] "regular expression extracting version from HTML page or repository tag")
+                        .value_parser(parse_pattern),
+                )
+                .arg(
+                    arg!(--"no-pattern" "look for version in links of HTML page")
+                        .conflicts_with("pattern"),
+                )
+                .arg(arg!(--"json-path" <PATH> "path of version field in JSON document").required(false))
+                .arg(
+                    arg!(--"no-json-path" "look for version with the source, not at a JSON path")
+                        .conflicts_with("json-path"),
+                )
+                .arg(arg!(--"scan-text" "look for versions in text, option values and data-version attributes of HTML page, not only in links"))
+                .arg(
+                    arg!(--"no-scan-text" "look for versions only in links of HTML page")
+                        .conflicts_with("scan-text"),
+                )
+                .arg(
+                    arg!(--selector [SELECTOR] "CSS selector of HTML page parts to look at, like `#stable a`")
+                        .value_parser(parse_selector),
+                )
+                .arg(
+                    arg!(--"no-selector" "look at the whole HTML page")
+                        .conflicts_with("selector"),
+                )
+                .arg(arg!(--descend "look for versions in the highest version subdirectory of HTML directory listing, like `1.24/`"))
+                .arg(
+                    arg!(--"no-descend" "look for versions in HTML directory listing itself")
+                        .conflicts_with("descend"),
+                )
+                .arg(
+                    arg!(-m --mirror <URL> "replace fallback master sites; may be repeated")
+                        .required(false)
+                        .action(ArgAction::Append),
+                )
+                .arg(arg!(--"no-mirrors" "remove fallback master sites").conflicts_with("mirror"))
+                .arg(arg!(--"all-releases" "look at all GitHub releases, including pre-releases, not only the latest one"))
+                .arg(
+                    arg!(--"latest-release" "look at the latest GitHub release only")
+                        .conflicts_with("all-releases"),
+                )
                 .arg(arg!(<pkg> "package name")),
         )
         .get_matches();
 
-    // TODO: database path from config
-    let db_path = matches.get_one::<String>("db").expect("database path");
-    let pool = SqlitePool::connect(&format!("sqlite:{db_path}")).await?;
-
-    // read config
-    let mut github_account = None;
-    let mut github_token = None;
-    if let Some(path) = matches.get_one::<PathBuf>("config") {
-        if let Ok(data) = read(path) {
-            let config: Table = String::from_utf8_lossy(&data).parse()?;
-            if let Some(github) = config.get("github") {
-                github_account = github.get("account").and_then(|value| {
-                    if let Value::String(s) = value {
-                        Some(s.clone())
-                    } else {
-                        None
-                    }
-                });
-                github_token = github.get("token").and_then(|value| {
-                    if let Value::String(s) = value {
-                        Some(s.clone())
-                    } else {
-                        None
-                    }
-                });
+    let verbose = matches.get_flag("verbose");
+    let exact = matches.get_flag("exact");
+    let dry_run = matches.get_flag("dry-run");
+    let json = matches.get_one::<String>("format").map(String::as_str) == Some("json");
+    let color = !json
+        && use_color(
+            matches
+                .get_one::<String>("color")
+                .expect("color has default"),
+        );
+
+    // read config; one given on command line must exist
+    let explicit_config = matches.get_one::<PathBuf>("config");
+    let config_path = explicit_config.cloned().unwrap_or_else(default_config_path);
+    if verbose {
+        if config_path.is_file() {
+            eprintln!("Using configuration {}", config_path.display());
+        } else {
+            eprintln!("No configuration {}, using defaults", config_path.display());
+        }
+    }
+    let mut config = if explicit_config.is_some() || config_path.is_file() {
+        Config::from_file(&config_path)?
+    } else {
+        Config::default()
+    };
+
+    // database path: command line, then config, then default
+    let db_path = match (matches.get_one::<PathBuf>("db"), &config.database) {
+        (Some(path), _) => path.clone(),
+        (None, Some(database)) => PathBuf::from(database),
+        (None, None) => {
+            let path = default_database_path();
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                create_dir_all(dir)?;
             }
+            path
         }
+    };
+    if verbose {
+        eprintln!("Using database {}", db_path.display());
     }
+    let pool = db::connect(&db_path.to_string_lossy()).await?;
 
+    // command could not do all it was asked to, exit with failure status
+    let mut failed = false;
     match matches.subcommand() {
         Some(("add", submatches)) => {
-            let pkg = Package::add(
-                &pool,
-                submatches
-                    .get_one::<String>("pkg")
-                    .expect("pkg is required")
-                    .into(),
-                submatches
+            if let Some(path) = submatches.get_one::<PathBuf>("from") {
+                let (mut added, mut skipped, mut errors) = (0, 0, 0);
+                for (location, spec) in read_specs(path)? {
+                    let spec = match spec.and_then(|spec| validate_spec(&spec).map(|()| spec)) {
+                        Ok(spec) => spec,
+                        Err(err) => {
+                            eprintln!("{location}: {err}");
+                            errors += 1;
+                            continue;
+                        }
+                    };
+                    if Package::exists(&pool, &spec.distname).await? {
+                        skipped += 1;
+                        continue;
+                    }
+                    let source = detect_source(&spec.master_site, &config);
+                    match Package::add(
+                        &pool,
+                        spec.distname,
+                        spec.master_site,
+                        spec.version,
+                        None,
+                        source.as_str(),
+                    )
+                    .await
+                    {
+                        Ok(_) => added += 1,
+                        Err(err) => {
+                            eprintln!("{location}: {err}");
+                            errors += 1;
+                        }
+                    }
+                }
+                println!("Added {added}, skipped {skipped} (already exist), failed {errors}");
+                failed = errors > 0;
+            } else {
+                let url = submatches
                     .get_one::<String>("url")
-                    .expect("url is required")
-                    .into(),
-                submatches
-                    .get_one::<String>("release")
-                    .expect("release is required")
-                    .into(),
-            )
-            .await?;
-            println!("added {pkg}");
-            return Ok(());
+                    .expect("url is required");
+                let source = match submatches.get_one::<String>("source") {
+                    Some(name) => parse_source(name, &config)?,
+                    None => detect_source(url, &config).to_string(),
+                };
+                let release = submatches.get_one::<String>("release");
+                let flag_value = |name| submatches.get_one::<String>(name).cloned();
+                let mut spec = PackageSpec {
+                    distname: submatches
+                        .get_one::<String>("pkg")
+                        .expect("pkg is required")
+                        .into(),
+                    master_site: url.into(),
+                    version: release.cloned().unwrap_or_default(),
+                    local_version: None,
+                    source: None,
+                    stable_only: submatches.get_flag("stable-only"),
+                    held: false,
+                    pattern: flag_value("pattern"),
+                    json_path: flag_value("json-path"),
+                    scan_text: submatches.get_flag("scan-text"),
+                    mirrors: submatches
+                        .get_many::<String>("mirror")
+                        .unwrap_or_default()
+                        .cloned()
+                        .collect(),
+                    all_releases: submatches.get_flag("all-releases"),
+                    selector: flag_value("selector"),
+                    descend: submatches.get_flag("descend"),
+                    semver: submatches.get_flag("semver"),
+                    ignore: flag_value("ignore"),
+                    pinned: None,
+                    tag_prefix: flag_value("tag-prefix"),
+                    tag_suffix: flag_value("tag-suffix"),
+                    epoch: submatches
+                        .get_one::<u16>("epoch")
+                        .map(|epoch| i64::from(*epoch)),
+                };
+                let check_interval = submatches.get_one::<i64>("interval").copied();
+                // look for version before storing anything, so the database isn't locked
+                // meanwhile; nothing is stored if version detection fails
+                if release.is_none() {
+                    let mut pkg = Package::draft(&spec, &source, check_interval);
+                    let client = http_client(&config.http)?;
+                    let mut notes = Vec::new();
+                    if !pkg.auto_check(&client, &config, &mut notes).await? {
+                        let reason = notes
+                            .pop()
+                            .unwrap_or_else(|| format!("No version for {}", pkg.distname()));
+                        return Err(reason.into());
+                    }
+                    // version found upstream may still be unusable as local version
+                    parse_release(pkg.version())?;
+                    spec.version = pkg.version().into();
+                }
+                let mut tx = pool.begin().await?;
+                let pkg = Package::add_spec(&mut tx, spec, &source, check_interval).await?;
+                tx.commit().await?;
+                println!("added {pkg}");
+            }
         }
         Some(("check", submatches)) => {
-            if let Some(name) = submatches.get_one::<String>("pkg") {
-                let mut pkg = Package::fetch_by_name(&pool, name).await?;
-                if pkg
-                    .auto_check(github_account.as_ref(), github_token.as_ref())
-                    .await
-                {
-                    pkg.store_version(&pool).await.unwrap();
-                } else {
-                    pkg.update_last_check(&pool).await.unwrap();
+            if submatches.get_flag("no-webhook") {
+                config.webhook.url = None;
+            }
+            if submatches.get_flag("follow-redirects") {
+                config.check.follow_redirects = true;
+            }
+            if let Some(concurrency) = submatches.get_one::<usize>("concurrency") {
+                config.check.concurrency = *concurrency;
+            }
+            // dry run stores nothing, so there is nothing to notify about
+            let notify_updated =
+                !dry_run && matches!(submatches.try_get_one::<bool>("notify"), Ok(Some(true)));
+            let client = http_client(&config.http)?;
+            let redirect_client = if config.check.follow_redirects {
+                Some(redirect_client(&config.http)?)
+            } else {
+                None
+            };
+            let names: Vec<&String> = submatches
+                .get_many::<String>("pkg")
+                .unwrap_or_default()
+                .collect();
+            if !names.is_empty() {
+                let mut results = Vec::new();
+                let mut summary = CheckSummary::default();
+                for name in &names {
+                    let mut pkg = match Package::find(&pool, name, exact).await {
+                        Ok(pkg) => pkg,
+                        Err(FindError::Db(err)) => return Err(err.into()),
+                        // check the others anyway
+                        Err(err) => {
+                            eprintln!("Error: {err}");
+                            summary.failed.push((*name).clone());
+                            failed = true;
+                            continue;
+                        }
+                    };
+                    let result = pkg
+                        .check(&pool, &client, redirect_client.as_ref(), &config, dry_run)
+                        .await?;
+                    if notify_updated && result.updated {
+                        notify(&result);
+                    }
+                    failed |= result.error.is_some();
+                    if json {
+                        print_notes(&result);
+                    } else {
+                        print_check_result(&result);
+                        print!("{}", pkg.details());
+                    }
+                    summary.add(&result);
+                    results.push(result);
+                }
+                if json {
+                    // single package as before, several as array
+                    match (&names[..], &results[..]) {
+                        ([_], [result]) => println!("{}", serde_json::to_string(result)?),
+                        _ => println!("{}", serde_json::to_string(&results)?),
+                    }
+                } else if names.len() > 1 || dry_run {
+                    print_summary(&summary, dry_run);
                 }
-                pkg.display_info();
             } else {
-                Package::check_all(&pool, github_account.as_ref(), github_token.as_ref()).await;
+                let max_age = if submatches.get_flag("force") {
+                    None
+                } else {
+                    let seconds = submatches
+                        .get_one::<u64>("max-age")
+                        .copied()
+                        .unwrap_or(config.check.interval);
+                    Some(Duration::from_secs(seconds))
+                };
+                let results = Package::check_all(
+                    &pool,
+                    &client,
+                    redirect_client.as_ref(),
+                    &config,
+                    max_age,
+                    dry_run,
+                )
+                .inspect(|result| {
+                    if notify_updated && result.updated {
+                        notify(result);
+                    }
+                    failed |= result.error.is_some();
+                });
+                if json {
+                    let results: Vec<CheckResult> = results.inspect(print_notes).collect().await;
+                    println!("{}", serde_json::to_string(&results)?);
+                } else {
+                    let summary = results
+                        .fold(CheckSummary::default(), |mut summary, result| {
+                            print_check_result(&result);
+                            summary.add(&result);
+                            ready(summary)
+                        })
+                        .await;
+                    print_summary(&summary, dry_run);
+                }
             }
         }
         Some(("delete", submatches)) => {
             let name = submatches
                 .get_one::<String>("pkg")
                 .expect("pkg is required");
-            let pkg = Package::fetch_by_name(&pool, name).await?;
+            // deleting takes the full name
+            let pkg = Package::find(&pool, name, true).await?;
+            let distname = pkg.distname().to_string();
             pkg.delete(&pool).await?;
+            println!("Removed {distname}");
+        }
+        Some(("export", submatches)) => {
+            let list = PackageList {
+                package: Package::all_from_db(&pool)
+                    .await?
+                    .iter()
+                    .map(Package::spec)
+                    .collect(),
+            };
+            let data = if json {
+                serde_json::to_string_pretty(&list)?
+            } else {
+                toml::to_string(&list)?
+            };
+            match submatches.get_one::<PathBuf>("output") {
+                Some(path) => write(path, data)?,
+                None => print!("{data}"),
+            }
+        }
+        Some(("feed", submatches)) => {
+            let limit = *submatches
+                .get_one::<i64>("limit")
+                .expect("limit has default");
+            let changes = Package::recent_changes(&pool, limit).await?;
+            let data = feed::atom(&changes, OffsetDateTime::now_utc());
+            match submatches.get_one::<PathBuf>("output") {
+                Some(path) => write(path, data)?,
+                None => print!("{data}"),
+            }
+        }
+        Some(("history", submatches)) => {
+            let name = submatches
+                .get_one::<String>("pkg")
+                .expect("pkg is required");
+            let pkg = Package::find(&pool, name, exact).await?;
+            let history = pkg.history(&pool).await?;
+            if json {
+                println!("{}", serde_json::to_string(&history)?);
+            } else {
+                for change in &history {
+                    println!(
+                        "{} {} -> {}",
+                        change.observed_at.format(&Rfc3339)?,
+                        change.old_version,
+                        change.new_version
+                    );
+                }
+            }
+        }
+        Some((cmd @ ("hold" | "unhold"), submatches)) => {
+            let name = submatches
+                .get_one::<String>("pkg")
+                .expect("pkg is required");
+            let mut pkg = Package::find(&pool, name, exact).await?;
+            pkg.set_held(&pool, cmd == "hold").await?;
+        }
+        Some(("pin", submatches)) => {
+            let name = submatches
+                .get_one::<String>("pkg")
+                .expect("pkg is required");
+            let mut pkg = Package::find(&pool, name, exact).await?;
+            let version = submatches
+                .get_one::<String>("version")
+                .map(String::as_str)
+                .or(pkg.local_version())
+                .unwrap_or(pkg.version())
+                .to_string();
+            pkg.set_pinned(&pool, Some(version)).await?;
+        }
+        Some(("unpin", submatches)) => {
+            let name = submatches
+                .get_one::<String>("pkg")
+                .expect("pkg is required");
+            let mut pkg = Package::find(&pool, name, exact).await?;
+            pkg.set_pinned(&pool, None).await?;
+        }
+        Some(("import", submatches)) => {
+            let path = submatches
+                .get_one::<PathBuf>("file")
+                .expect("file is required");
+            let data = read_to_string(path)?;
+            // parse everything before touching the database
+            let mut list: PackageList = if path.extension().is_some_and(|ext| ext == "json") {
+                serde_json::from_str(&data)?
+            } else {
+                toml::from_str(&data)?
+            };
+            for (index, spec) in list.package.iter_mut().enumerate() {
+                validate_spec(spec)
+                    .map_err(|err| format!("{} entry {}: {err}", path.display(), index + 1))?;
+                match &spec.source {
+                    Some(name) => {
+                        parse_source(name, &config).map_err(|err| {
+                            format!("{} entry {}: {err}", path.display(), index + 1)
+                        })?;
+                    }
+                    None => {
+                        spec.source = Some(detect_source(&spec.master_site, &config).to_string())
+                    }
+                }
+            }
+            let summary =
+                Package::import(&pool, list.package, submatches.get_flag("overwrite")).await?;
+            println!(
+                "Added {}, updated {}, skipped {} (already exist)",
+                summary.added, summary.updated, summary.skipped
+            );
         }
         Some(("info", submatches)) => {
-            if let Some(name) = submatches.get_one::<String>("pkg") {
-                let pkg = Package::fetch_by_name(&pool, name).await?;
-                pkg.display_info();
+            if submatches.get_flag("errors") {
+                let pkgs = Package::failed(&pool).await?;
+                if json {
+                    let infos: Vec<_> = pkgs.iter().map(Package::info).collect();
+                    println!("{}", serde_json::to_string(&infos)?);
+                } else {
+                    print_errors(&pkgs);
+                }
+            } else if let Some(name) = submatches.get_one::<String>("pkg") {
+                let pkg = Package::find(&pool, name, exact).await?;
+                if json {
+                    println!("{}", serde_json::to_string(&pkg.info())?);
+                } else {
+                    print!("{}", pkg.details());
+                }
+            } else if json {
+                let pkgs = Package::outdated(&pool).await?;
+                let infos: Vec<_> = pkgs.iter().map(Package::info).collect();
+                println!("{}", serde_json::to_string(&infos)?);
             } else {
-                Package::info_stream(&pool).await;
+                Package::outdated_stream(&pool)
+                    .for_each(|pkg| {
+                        println!("{pkg}");
+                        ready(())
+                    })
+                    .await;
                 let total = Package::total(&pool).await?;
                 println!("Total {total}");
             }
         }
+        Some(("list", submatches)) => {
+            let mut pkgs = Package::all_from_db(&pool).await?;
+            if submatches.get_flag("outdated") {
+                pkgs.retain(Package::is_outdated);
+            }
+            let errors = submatches.get_flag("errors");
+            if errors {
+                pkgs.retain(|pkg| pkg.last_error().is_some());
+            }
+            if submatches.get_one::<String>("sort").map(String::as_str) == Some("version") {
+                Package::sort_by_version(&mut pkgs);
+            }
+            if json {
+                let infos: Vec<_> = pkgs.iter().map(Package::info).collect();
+                println!("{}", serde_json::to_string(&infos)?);
+            } else if errors {
+                print_errors(&pkgs);
+            } else {
+                print_table(&pkgs, color);
+            }
+        }
         Some(("mark", submatches)) => {
             let name = submatches
                 .get_one::<String>("pkg")
                 .expect("pkg is required");
-            let mut pkg = Package::fetch_by_name(&pool, name).await?;
-            pkg.mark_latest(&pool).await?;
+            let mut pkg = Package::find(&pool, name, exact).await?;
+            match pkg.mark_latest(&pool).await? {
+                MarkOutcome::AlreadyLatest => println!(
+                    "Package {} already has latest version {}",
+                    pkg.distname(),
+                    pkg.version()
+                ),
+                MarkOutcome::Marked(Some(old_local_version)) => println!(
+                    "Package {} updated from {} to {}",
+                    pkg.distname(),
+                    old_local_version,
+                    pkg.version()
+                ),
+                MarkOutcome::Marked(None) => println!(
+                    "Package {} version set to {}",
+                    pkg.distname(),
+                    pkg.version()
+                ),
+            }
+        }
+        Some(("rename", submatches)) => {
+            let name = submatches
+                .get_one::<String>("pkg")
+                .expect("pkg is required");
+            let new_name = submatches
+                .get_one::<String>("name")
+                .expect("name is required");
+            let mut pkg = Package::find(&pool, name, exact).await?;
+            let old_name = pkg.distname().to_owned();
+            match pkg.rename(&pool, new_name.clone()).await {
+                Err(err)
+                    if err
+                        .as_database_error()
+                        .is_some_and(|err| err.is_unique_violation()) =>
+                {
+                    return Err(format!("package {new_name} already exists").into());
+                }
+                result => result?,
+            }
+            println!("Package {old_name} renamed to {new_name}");
+        }
+        Some(("search", submatches)) => {
+            let pattern = submatches
+                .get_one::<String>("pattern")
+                .expect("pattern is required");
+            let pkgs = Package::search(&pool, pattern, submatches.get_flag("master-site")).await?;
+            if json {
+                let infos: Vec<_> = pkgs.iter().map(Package::info).collect();
+                println!("{}", serde_json::to_string(&infos)?);
+            } else {
+                for pkg in &pkgs {
+                    println!("{pkg}");
+                }
+            }
+        }
+        Some(("stats", _)) => {
+            let pkgs = Package::all_from_db(&pool).await?;
+            let stats = Package::stats(&pkgs);
+            if json {
+                println!("{}", serde_json::to_string(&stats)?);
+            } else {
+                println!("{:<12} {:>8} {:>8}", "Source", "Total", "Outdated");
+                for source in &stats {
+                    println!(
+                        "{:<12} {:>8} {:>8}",
+                        source.source, source.total, source.outdated
+                    );
+                }
+                let outdated: usize = stats.iter().map(|source| source.outdated).sum();
+                println!("Total {}, outdated {outdated}", pkgs.len());
+            }
+        }
+        Some(("unmark", submatches)) => {
+            let name = submatches
+                .get_one::<String>("pkg")
+                .expect("pkg is required");
+            let mut pkg = Package::find(&pool, name, exact).await?;
+            match pkg.unmark(&pool).await? {
+                UnmarkOutcome::NotMarked => {
+                    eprintln!(
+                        "Package {} was not marked, nothing to revert",
+                        pkg.distname()
+                    );
+                    failed = true;
+                }
+                UnmarkOutcome::Changed(marked) => {
+                    eprintln!(
+                        "Package {} local version changed since marked as {}, not reverting",
+                        pkg.distname(),
+                        marked
+                    );
+                    failed = true;
+                }
+                UnmarkOutcome::Reverted(marked, Some(old_local_version)) => println!(
+                    "Package {} reverted from {} to {}",
+                    pkg.distname(),
+                    marked,
+                    old_local_version
+                ),
+                UnmarkOutcome::Reverted(_, None) => {
+                    println!("Package {} version unset", pkg.distname());
+                }
+            }
         }
         Some(("update", submatches)) => {
             let name = submatches
                 .get_one::<String>("pkg")
                 .expect("pkg is required");
-            let mut pkg = Package::fetch_by_name(&pool, name).await?;
+            let mut pkg = Package::find(&pool, name, exact).await?;
+            let url = submatches.get_one::<String>("url");
+            // new master site may need another source
+            let source = match submatches.get_one::<String>("source") {
+                Some(name) => Some(parse_source(name, &config)?),
+                None => url.map(|url| detect_source(url, &config).to_string()),
+            };
             pkg.update(
                 &pool,
                 submatches.get_one::<String>("name").cloned(),
-                submatches.get_one::<String>("url").cloned(),
+                url.cloned(),
                 submatches.get_one::<String>("release").cloned(),
+                submatches.get_one::<i64>("interval").copied(),
+                source.as_deref(),
             )
             .await?;
+            if submatches.get_flag("stable-only") {
+                pkg.set_stable_only(&pool, true).await?;
+            } else if submatches.get_flag("prerelease") {
+                pkg.set_stable_only(&pool, false).await?;
+            }
+            if submatches.get_flag("semver") {
+                pkg.set_semver(&pool, true).await?;
+            } else if submatches.get_flag("no-semver") {
+                pkg.set_semver(&pool, false).await?;
+            }
+            if let Some(ignore) = submatches.get_one::<String>("ignore") {
+                pkg.set_ignore(&pool, Some(ignore.clone())).await?;
+            } else if submatches.get_flag("no-ignore") {
+                pkg.set_ignore(&pool, None).await?;
+            }
+            if let Some(tag_prefix) = submatches.get_one::<String>("tag-prefix") {
+                pkg.set_tag_prefix(&pool, Some(tag_prefix.clone())).await?;
+            } else if submatches.get_flag("no-tag-prefix") {
+                pkg.set_tag_prefix(&pool, None).await?;
+            }
+            if let Some(tag_suffix) = submatches.get_one::<String>("tag-suffix") {
+                pkg.set_tag_suffix(&pool, Some(tag_suffix.clone())).await?;
+            } else if submatches.get_flag("no-tag-suffix") {
+                pkg.set_tag_suffix(&pool, None).await?;
+            }
+            if let Some(epoch) = submatches.get_one::<u16>("epoch") {
+                pkg.set_epoch(&pool, Some(i64::from(*epoch))).await?;
+            } else if submatches.get_flag("no-epoch") {
+                pkg.set_epoch(&pool, None).await?;
+            }
+            if let Some(pattern) = submatches.get_one::<String>("pattern") {
+                pkg.set_pattern(&pool, Some(pattern.clone())).await?;
+            } else if submatches.get_flag("no-pattern") {
+                pkg.set_pattern(&pool, None).await?;
+            }
+            if let Some(json_path) = submatches.get_one::<String>("json-path") {
+                pkg.set_json_path(&pool, Some(json_path.clone())).await?;
+            } else if submatches.get_flag("no-json-path") {
+                pkg.set_json_path(&pool, None).await?;
+            }
+            if submatches.get_flag("scan-text") {
+                pkg.set_scan_text(&pool, true).await?;
+            } else if submatches.get_flag("no-scan-text") {
+                pkg.set_scan_text(&pool, false).await?;
+            }
+            if let Some(selector) = submatches.get_one::<String>("selector") {
+                pkg.set_selector(&pool, Some(selector.clone())).await?;
+            } else if submatches.get_flag("no-selector") {
+                pkg.set_selector(&pool, None).await?;
+            }
+            if submatches.get_flag("descend") {
+                pkg.set_descend(&pool, true).await?;
+            } else if submatches.get_flag("no-descend") {
+                pkg.set_descend(&pool, false).await?;
+            }
+            if let Some(mirrors) = submatches.get_many::<String>("mirror") {
+                pkg.set_mirrors(&pool, mirrors.cloned().collect()).await?;
+            } else if submatches.get_flag("no-mirrors") {
+                pkg.set_mirrors(&pool, Vec::new()).await?;
+            }
+            if submatches.get_flag("all-releases") {
+                pkg.set_all_releases(&pool, true).await?;
+            } else if submatches.get_flag("latest-release") {
+                pkg.set_all_releases(&pool, false).await?;
+            }
         }
         _ => unreachable!(),
     }
 
-    Ok(())
+    let mut code = ExitCode::SUCCESS;
+    if failed {
+        code = ExitCode::from(EXIT_FAILURE);
+    } else if matches.get_flag("exit-code")
+        && matches!(matches.subcommand_name(), Some("check" | "info" | "list"))
+        && Package::total(&pool).await? > 0
+    {
+        code = ExitCode::from(EXIT_OUTDATED);
+    }
+
+    pool.close().await;
+
+    Ok(code)
 }
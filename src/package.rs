@@ -1,25 +1,238 @@
-use super::version::{parse_html, Version};
-use futures::stream::{Stream, StreamExt};
-use reqwest::{header, StatusCode, Url};
+use super::{
+    backend::{Backend, Custom, Found, Query},
+    config::{Config, HttpConfig},
+    error::{CheckError, FindError},
+    http::send,
+    source::Source,
+    version::{epoch_release, tag_release, SemVer, Version},
+};
+use futures::{
+    future::ready,
+    stream::{Stream, StreamExt, TryStreamExt},
+};
+use regex::Regex;
+use reqwest::{header, Client, StatusCode, Url};
 use sqlx::{
-    query, query_as, query_scalar, sqlite::SqlitePool, types::time::OffsetDateTime,
+    query, query_as, query_scalar,
+    sqlite::{SqliteConnection, SqliteExecutor, SqlitePool},
+    types::time::OffsetDateTime,
     Error as SqlxError, FromRow,
 };
-use std::{fmt, iter::Iterator, pin::Pin, str::FromStr, time::Duration};
+use std::{collections::BTreeMap, fmt, iter::Iterator, pin::Pin, str::FromStr, time::Duration};
+use time::format_description::well_known::Rfc3339;
 
-#[derive(Deserialize)]
-struct PypiProject {
-    info: PypiProjectInfo,
+/// Outcome of checking a package for new version.
+#[derive(Serialize)]
+pub struct CheckResult {
+    pub distname: String,
+    pub master_site: String,
+    pub local_version: Option<String>,
+    /// Version known before the check.
+    pub old_version: String,
+    /// Version known after the check.
+    pub version: String,
+    pub updated: bool,
+    pub error: Option<String>,
+    /// Remarks about a check that did not fail, like no suitable version found upstream.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
 }
 
-#[derive(Deserialize)]
-struct PypiProjectInfo {
-    version: String,
+/// Outcome of marking a package as up to date.
+#[derive(Debug, PartialEq)]
+pub enum MarkOutcome {
+    /// Local version is already the latest one.
+    AlreadyLatest,
+    /// Local version set to the latest one; the previous local version, if any.
+    Marked(Option<String>),
+}
+
+/// Outcome of reverting the last mark.
+#[derive(Debug, PartialEq)]
+pub enum UnmarkOutcome {
+    /// Package was never marked.
+    NotMarked,
+    /// Local version changed since marked as this version; nothing reverted.
+    Changed(String),
+    /// Local version reverted from the marked version to the previous one, if any.
+    Reverted(String, Option<String>),
+}
+
+/// Outcome of looking for upstream version at one site.
+enum SiteCheck {
+    /// Version found; whether it is newer than the one known.
+    Found(bool),
+    /// No suitable version, which is noted.
+    Nothing,
+    /// Document not modified since the previous check.
+    NotModified,
+}
+
+/// Tally of check results.
+#[derive(Default)]
+pub struct CheckSummary {
+    pub checked: usize,
+    pub updated: usize,
+    /// Names of packages whose check failed.
+    pub failed: Vec<String>,
+}
+
+impl CheckSummary {
+    pub fn add(&mut self, result: &CheckResult) {
+        self.checked += 1;
+        if result.updated {
+            self.updated += 1;
+        }
+        if result.error.is_some() {
+            self.failed.push(result.distname.clone());
+        }
+    }
+}
+
+impl fmt::Display for CheckSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Checked {}, updated {}, failed {}",
+            self.checked,
+            self.updated,
+            self.failed.len()
+        )?;
+        if !self.failed.is_empty() {
+            write!(f, ": {}", self.failed.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Package information for serialized output.
+#[derive(Serialize)]
+pub struct PackageInfo<'a> {
+    pub distname: &'a str,
+    pub master_site: &'a str,
+    pub version: &'a str,
+    pub local_version: Option<&'a str>,
+    pub source: &'a str,
+    pub stable_only: bool,
+    pub held: bool,
+    pub pattern: Option<&'a str>,
+    pub json_path: Option<&'a str>,
+    pub scan_text: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<&'a str>,
+    pub all_releases: bool,
+    pub selector: Option<&'a str>,
+    pub descend: bool,
+    pub semver: bool,
+    pub ignore: Option<&'a str>,
+    pub pinned: Option<&'a str>,
+    pub tag_prefix: Option<&'a str>,
+    pub tag_suffix: Option<&'a str>,
+    pub epoch: Option<i64>,
+    /// RFC 3339 timestamp
+    pub last_check: String,
+    pub outdated: bool,
+    pub last_error: Option<&'a str>,
+    /// RFC 3339 timestamp
+    pub last_error_at: Option<String>,
+}
+
+/// Portable package description for export and import.
+#[derive(Deserialize, Serialize)]
+pub struct PackageSpec {
+    pub distname: String,
+    pub master_site: String,
+    pub version: String,
+    pub local_version: Option<String>,
+    /// Built-in source, or one defined in configuration. Detected from master site if missing.
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stable_only: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub held: bool,
+    /// Regular expression extracting version from HTML page or repository tag.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Path of version field in JSON document.
+    #[serde(default)]
+    pub json_path: Option<String>,
+    /// Look for versions in text of HTML page too.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub scan_text: bool,
+    /// Fallback master sites.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<String>,
+    /// Look at all GitHub releases, not only the latest one.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub all_releases: bool,
+    /// CSS selector of HTML page parts looked at for versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    /// Look for versions in the highest version subdirectory of directory listing.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub descend: bool,
+    /// Compare versions as semantic versions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub semver: bool,
+    /// Regular expression of versions to skip.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore: Option<String>,
+    /// Version package is pinned at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<String>,
+    /// Prefix of release tags, stripped to get version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_prefix: Option<String>,
+    /// Suffix of release tags, stripped to get version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_suffix: Option<String>,
+    /// Epoch of versions found upstream.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<i64>,
+}
+
+/// Package list as written by export.
+#[derive(Default, Deserialize, Serialize)]
+pub struct PackageList {
+    pub package: Vec<PackageSpec>,
+}
+
+/// Counts of packages processed by import.
+#[derive(Default)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub skipped: usize,
+}
+
+/// Package counts of one source.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SourceStats {
+    pub source: String,
+    pub total: usize,
+    /// Packages with newer version, except held ones.
+    pub outdated: usize,
 }
 
-#[derive(Deserialize)]
-struct GitHubReleaseInfo {
-    tag_name: String,
+/// Upstream version change observed by a check.
+#[derive(FromRow, Serialize)]
+pub struct VersionChange {
+    pub old_version: String,
+    pub new_version: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub observed_at: OffsetDateTime,
+}
+
+/// Upstream version change of a package, for feed of recent changes.
+#[derive(FromRow, Serialize)]
+pub struct PackageChange {
+    pub distname: String,
+    pub master_site: String,
+    pub old_version: String,
+    pub new_version: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub observed_at: OffsetDateTime,
 }
 
 #[derive(FromRow)]
@@ -30,36 +243,92 @@ pub struct Package {
     version: String,
     local_version: Option<String>,
     last_check: OffsetDateTime,
+    /// Seconds between checks; `None` means global check interval.
+    check_interval: Option<i64>,
+    /// Name of `Source`.
+    source: String,
+    /// Why the last check failed, if it did.
+    last_error: Option<String>,
+    last_error_at: Option<OffsetDateTime>,
+    /// Ignore pre-release versions.
+    stable_only: bool,
+    /// Kept at local version on purpose: not checked, nor reported as outdated.
+    held: bool,
+    /// Regular expression with one capture group extracting version from HTML page,
+    /// instead of looking at links, or from tag of GitHub, Bitbucket or git repository; tags
+    /// not matching are skipped.
+    pattern: Option<String>,
+    /// Path of version field in JSON document, for `json` source.
+    json_path: Option<String>,
+    /// `ETag` header of the last response, for conditional request.
+    etag: Option<String>,
+    /// `Last-Modified` header of the last response, for conditional request.
+    last_modified: Option<String>,
+    /// Look for versions in text of HTML page too, not only in links.
+    scan_text: bool,
+    /// Fallback master sites, one per line.
+    mirrors: String,
+    /// Look at all GitHub releases, including pre-releases, instead of the latest one only.
+    all_releases: bool,
+    /// CSS selector of HTML page parts where versions are looked for, like `#stable a`.
+    selector: Option<String>,
+    /// Look for versions in the highest version subdirectory of HTML directory listing, like
+    /// `1.24/`, instead of the listing itself.
+    descend: bool,
+    /// Compare local and upstream versions as semantic versions, so pre-release identifiers
+    /// like `rc.1` are ordered as specified by semver.
+    semver: bool,
+    /// Regular expression of upstream versions to skip, like `-beta` or a broken release.
+    ignore: Option<String>,
+    /// Version package is pinned at, like a long-term support release. Package is still
+    /// checked, but local version is compared with this one instead of upstream version.
+    pinned: Option<String>,
+    /// Prefix of release tags, like `jq-`; tags without it are skipped.
+    tag_prefix: Option<String>,
+    /// Suffix of release tags, like `-final`; tags without it are skipped.
+    tag_suffix: Option<String>,
+    /// Epoch given to versions found upstream which have none, as in `1:1.0`, so a scheme
+    /// starting over, like `2023.1` followed by `1.0`, is taken for newer.
+    epoch: Option<i64>,
 }
 
 impl Package {
-    pub async fn add(
-        pool: &SqlitePool,
+    pub async fn add<'e, E: SqliteExecutor<'e>>(
+        executor: E,
         distname: String,
         master_site: String,
         version: String,
+        check_interval: Option<i64>,
+        source: &str,
     ) -> Result<Self, SqlxError> {
         let last_check = OffsetDateTime::now_utc();
         query_as!(
             Self,
-            "INSERT INTO package (distname, master_site, version, local_version, last_check) \
-            VALUES ($1, $2, $3, $4, $5) RETURNING *",
+            "INSERT INTO package (distname, master_site, version, local_version, last_check, \
+            check_interval, source) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
             distname,
             master_site,
             version,
             version,
-            last_check
+            last_check,
+            check_interval,
+            source
         )
-        .fetch_one(pool)
-        .await
+        // step statement to completion, so the insert is committed
+        .fetch_all(executor)
+        .await?
+        .pop()
+        .ok_or(SqlxError::RowNotFound)
     }
 
-    pub async fn update(
+    pub async fn update<'e, E: SqliteExecutor<'e>>(
         &mut self,
-        pool: &SqlitePool,
+        executor: E,
         distname: Option<String>,
         master_site: Option<String>,
         version: Option<String>,
+        check_interval: Option<i64>,
+        source: Option<&str>,
     ) -> Result<(), SqlxError> {
         let mut run_query = false;
 
@@ -69,70 +338,358 @@ impl Package {
         }
         if let Some(master_site) = master_site {
             self.master_site = master_site;
+            self.forget_validators();
             run_query = true;
         }
         if let Some(version) = version {
             self.local_version = Some(version);
             run_query = true;
         }
+        if let Some(check_interval) = check_interval {
+            self.check_interval = Some(check_interval);
+            run_query = true;
+        }
+        if let Some(source) = source {
+            self.source = source.into();
+            self.forget_validators();
+            run_query = true;
+        }
 
         if run_query {
             query_as!(
                 Self,
-                "UPDATE package SET distname = $2, master_site = $3, local_version = $4 \
-                WHERE id = $1",
+                "UPDATE package SET distname = $2, master_site = $3, local_version = $4, \
+                check_interval = $5, source = $6, etag = $7, last_modified = $8 WHERE id = $1",
                 self.id,
                 self.distname,
                 self.master_site,
                 self.local_version,
+                self.check_interval,
+                self.source,
+                self.etag,
+                self.last_modified,
             )
-            .execute(pool)
+            .execute(executor)
             .await?;
         }
 
         Ok(())
     }
 
+    /// Add package described by `spec`, with every setting it has.
+    pub async fn add_spec(
+        conn: &mut SqliteConnection,
+        spec: PackageSpec,
+        source: &str,
+        check_interval: Option<i64>,
+    ) -> Result<Self, SqlxError> {
+        let mut pkg = Self::add(
+            &mut *conn,
+            spec.distname,
+            spec.master_site,
+            spec.version,
+            check_interval,
+            source,
+        )
+        .await?;
+        pkg.update(&mut *conn, None, None, spec.local_version, None, None)
+            .await?;
+        if spec.stable_only {
+            pkg.set_stable_only(&mut *conn, true).await?;
+        }
+        if spec.held {
+            pkg.set_held(&mut *conn, true).await?;
+        }
+        if spec.pattern.is_some() {
+            pkg.set_pattern(&mut *conn, spec.pattern).await?;
+        }
+        if spec.json_path.is_some() {
+            pkg.set_json_path(&mut *conn, spec.json_path).await?;
+        }
+        if spec.scan_text {
+            pkg.set_scan_text(&mut *conn, true).await?;
+        }
+        if !spec.mirrors.is_empty() {
+            pkg.set_mirrors(&mut *conn, spec.mirrors).await?;
+        }
+        if spec.all_releases {
+            pkg.set_all_releases(&mut *conn, true).await?;
+        }
+        if spec.selector.is_some() {
+            pkg.set_selector(&mut *conn, spec.selector).await?;
+        }
+        if spec.descend {
+            pkg.set_descend(&mut *conn, true).await?;
+        }
+        if spec.semver {
+            pkg.set_semver(&mut *conn, true).await?;
+        }
+        if spec.ignore.is_some() {
+            pkg.set_ignore(&mut *conn, spec.ignore).await?;
+        }
+        if spec.pinned.is_some() {
+            pkg.set_pinned(&mut *conn, spec.pinned).await?;
+        }
+        if spec.tag_prefix.is_some() {
+            pkg.set_tag_prefix(&mut *conn, spec.tag_prefix).await?;
+        }
+        if spec.tag_suffix.is_some() {
+            pkg.set_tag_suffix(&mut *conn, spec.tag_suffix).await?;
+        }
+        if spec.epoch.is_some() {
+            pkg.set_epoch(&mut *conn, spec.epoch).await?;
+        }
+        Ok(pkg)
+    }
+
+    /// Package described by `spec`, not stored, like to look for its upstream version before
+    /// adding it.
+    #[must_use]
+    pub fn draft(spec: &PackageSpec, source: &str, check_interval: Option<i64>) -> Self {
+        Self {
+            id: 0,
+            distname: spec.distname.clone(),
+            master_site: spec.master_site.clone(),
+            version: spec.version.clone(),
+            local_version: spec.local_version.clone(),
+            last_check: OffsetDateTime::now_utc(),
+            check_interval,
+            source: source.into(),
+            last_error: None,
+            last_error_at: None,
+            stable_only: spec.stable_only,
+            held: spec.held,
+            pattern: spec.pattern.clone(),
+            json_path: spec.json_path.clone(),
+            etag: None,
+            last_modified: None,
+            scan_text: spec.scan_text,
+            mirrors: spec.mirrors.join("\n"),
+            all_releases: spec.all_releases,
+            selector: spec.selector.clone(),
+            descend: spec.descend,
+            semver: spec.semver,
+            ignore: spec.ignore.clone(),
+            pinned: spec.pinned.clone(),
+            tag_prefix: spec.tag_prefix.clone(),
+            tag_suffix: spec.tag_suffix.clone(),
+            epoch: spec.epoch,
+        }
+    }
+
+    /// Import packages in a single transaction. Existing packages are skipped, or updated
+    /// if `overwrite` is set.
+    pub async fn import(
+        pool: &SqlitePool,
+        specs: Vec<PackageSpec>,
+        overwrite: bool,
+    ) -> Result<ImportSummary, SqlxError> {
+        let mut summary = ImportSummary::default();
+        let mut tx = pool.begin().await?;
+
+        for spec in specs {
+            let existing = query_as!(
+                Self,
+                "SELECT id \"id!\", distname \"distname!\", master_site \"master_site!\", version \"version!\", local_version, last_check, check_interval, source, last_error, last_error_at, stable_only, held, pattern, json_path, etag, last_modified, scan_text, mirrors, all_releases, selector, descend, semver, ignore, pinned, tag_prefix, tag_suffix, epoch \
+                FROM package WHERE distname = $1",
+                spec.distname
+            ).fetch_optional(&mut *tx).await?;
+
+            if let Some(mut pkg) = existing {
+                if overwrite {
+                    pkg.update(
+                        &mut *tx,
+                        None,
+                        Some(spec.master_site),
+                        spec.local_version,
+                        None,
+                        spec.source.as_deref(),
+                    )
+                    .await?;
+                    pkg.set_stable_only(&mut *tx, spec.stable_only).await?;
+                    pkg.set_held(&mut *tx, spec.held).await?;
+                    pkg.set_pattern(&mut *tx, spec.pattern).await?;
+                    pkg.set_json_path(&mut *tx, spec.json_path).await?;
+                    pkg.set_scan_text(&mut *tx, spec.scan_text).await?;
+                    pkg.set_mirrors(&mut *tx, spec.mirrors).await?;
+                    pkg.set_all_releases(&mut *tx, spec.all_releases).await?;
+                    pkg.set_selector(&mut *tx, spec.selector).await?;
+                    pkg.set_descend(&mut *tx, spec.descend).await?;
+                    pkg.set_semver(&mut *tx, spec.semver).await?;
+                    pkg.set_ignore(&mut *tx, spec.ignore).await?;
+                    pkg.set_pinned(&mut *tx, spec.pinned).await?;
+                    pkg.set_tag_prefix(&mut *tx, spec.tag_prefix).await?;
+                    pkg.set_tag_suffix(&mut *tx, spec.tag_suffix).await?;
+                    pkg.set_epoch(&mut *tx, spec.epoch).await?;
+                    summary.updated += 1;
+                } else {
+                    summary.skipped += 1;
+                }
+            } else {
+                let source = spec
+                    .source
+                    .clone()
+                    .unwrap_or_else(|| Source::detect(&spec.master_site).to_string());
+                Self::add_spec(&mut tx, spec, &source, None).await?;
+                summary.added += 1;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(summary)
+    }
+
+    #[must_use]
+    pub fn spec(&self) -> PackageSpec {
+        PackageSpec {
+            distname: self.distname.clone(),
+            master_site: self.master_site.clone(),
+            version: self.version.clone(),
+            local_version: self.local_version.clone(),
+            source: Some(self.source.clone()),
+            stable_only: self.stable_only,
+            held: self.held,
+            pattern: self.pattern.clone(),
+            json_path: self.json_path.clone(),
+            scan_text: self.scan_text,
+            mirrors: self.mirrors().map(Into::into).collect(),
+            all_releases: self.all_releases,
+            selector: self.selector.clone(),
+            descend: self.descend,
+            semver: self.semver,
+            ignore: self.ignore.clone(),
+            pinned: self.pinned.clone(),
+            tag_prefix: self.tag_prefix.clone(),
+            tag_suffix: self.tag_suffix.clone(),
+            epoch: self.epoch,
+        }
+    }
+
     pub async fn fetch_by_name(pool: &SqlitePool, name: &str) -> Result<Self, SqlxError> {
         query_as!(
             Self,
-            "SELECT id, distname \"distname!\", master_site \"master_site!\", version \"version!\", local_version, last_check \
+            "SELECT id \"id!\", distname \"distname!\", master_site \"master_site!\", version \"version!\", local_version, last_check, check_interval, source, last_error, last_error_at, stable_only, held, pattern, json_path, etag, last_modified, scan_text, mirrors, all_releases, selector, descend, semver, ignore, pinned, tag_prefix, tag_suffix, epoch \
             FROM package WHERE distname = $1",
             name
         ).fetch_one(pool).await
     }
 
-    pub async fn all_from_db(pool: &SqlitePool) -> Result<Vec<Self>, SqlxError> {
-        let pkgs = query_as!(
+    /// Find package by exact name or, unless `exact` is set, by unambiguous prefix or
+    /// substring of its name.
+    pub async fn find(pool: &SqlitePool, name: &str, exact: bool) -> Result<Self, FindError> {
+        let mut candidates = query_as!(
             Self,
-            "SELECT id, distname \"distname!\", master_site \"master_site!\", version \"version!\", local_version, last_check \
-            FROM package ORDER BY distname",
+            "SELECT id \"id!\", distname \"distname!\", master_site \"master_site!\", version \"version!\", local_version, last_check, check_interval, source, last_error, last_error_at, stable_only, held, pattern, json_path, etag, last_modified, scan_text, mirrors, all_releases, selector, descend, semver, ignore, pinned, tag_prefix, tag_suffix, epoch \
+            FROM package WHERE instr(distname, $1) > 0 ORDER BY distname",
+            name
         ).fetch_all(pool).await?;
-
-        for pkg in &pkgs {
-            println!("{pkg}");
+        if let Some(index) = candidates.iter().position(|pkg| pkg.distname == name) {
+            return Ok(candidates.swap_remove(index));
+        }
+        if exact {
+            return Err(FindError::NotFound(name.into()));
+        }
+        // prefix matches take precedence over other substrings
+        if candidates.iter().any(|pkg| pkg.distname.starts_with(name)) {
+            candidates.retain(|pkg| pkg.distname.starts_with(name));
+        }
+        match candidates.len() {
+            0 => Err(FindError::NotFound(name.into())),
+            1 => Ok(candidates.remove(0)),
+            _ => Err(FindError::Ambiguous(
+                name.into(),
+                candidates.into_iter().map(|pkg| pkg.distname).collect(),
+            )),
         }
+    }
+
+    /// Fetch packages with `pattern` in name, or with `in_master_site` also in master site,
+    /// ignoring case.
+    pub async fn search(
+        pool: &SqlitePool,
+        pattern: &str,
+        in_master_site: bool,
+    ) -> Result<Vec<Self>, SqlxError> {
+        // match wildcards literally
+        let pattern = pattern
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        query_as!(
+            Self,
+            "SELECT id \"id!\", distname \"distname!\", master_site \"master_site!\", version \"version!\", local_version, last_check, check_interval, source, last_error, last_error_at, stable_only, held, pattern, json_path, etag, last_modified, scan_text, mirrors, all_releases, selector, descend, semver, ignore, pinned, tag_prefix, tag_suffix, epoch \
+            FROM package WHERE distname LIKE '%' || $1 || '%' ESCAPE '\\' \
+            OR ($2 AND master_site LIKE '%' || $1 || '%' ESCAPE '\\') ORDER BY distname",
+            pattern,
+            in_master_site
+        ).fetch_all(pool).await
+    }
 
-        Ok(pkgs)
+    pub async fn all_from_db(pool: &SqlitePool) -> Result<Vec<Self>, SqlxError> {
+        query_as!(
+            Self,
+            "SELECT id \"id!\", distname \"distname!\", master_site \"master_site!\", version \"version!\", local_version, last_check, check_interval, source, last_error, last_error_at, stable_only, held, pattern, json_path, etag, last_modified, scan_text, mirrors, all_releases, selector, descend, semver, ignore, pinned, tag_prefix, tag_suffix, epoch \
+            FROM package ORDER BY distname",
+        ).fetch_all(pool).await
     }
 
-    pub async fn total(pool: &SqlitePool) -> Result<i32, SqlxError> {
-        query_scalar!("SELECT count(*) FROM package WHERE local_version != version")
+    /// Check if package with given name is tracked.
+    pub async fn exists(pool: &SqlitePool, name: &str) -> Result<bool, SqlxError> {
+        let count = query_scalar!("SELECT count(*) FROM package WHERE distname = $1", name)
             .fetch_one(pool)
+            .await?;
+        Ok(count > 0)
+    }
+
+    /// Sort packages by parsed version, unparseable versions first.
+    pub fn sort_by_version(pkgs: &mut [Self]) {
+        pkgs.sort_by_cached_key(|pkg| Version::from_str(&pkg.version).ok());
+    }
+
+    /// Count outdated packages, compared as by `is_outdated`.
+    pub async fn total(pool: &SqlitePool) -> Result<usize, SqlxError> {
+        Self::stream(pool)
+            .try_fold(0, |total, pkg| {
+                ready(Ok(total + usize::from(pkg.is_outdated())))
+            })
             .await
     }
 
-    /// Build asynchronous stream to fetch all packages.
+    /// Count packages, and outdated ones, of every source, ordered by source name.
+    #[must_use]
+    pub fn stats(pkgs: &[Self]) -> Vec<SourceStats> {
+        let mut stats: BTreeMap<&str, SourceStats> = BTreeMap::new();
+        for pkg in pkgs {
+            let entry = stats.entry(&pkg.source).or_insert_with(|| SourceStats {
+                source: pkg.source.clone(),
+                total: 0,
+                outdated: 0,
+            });
+            entry.total += 1;
+            if pkg.is_outdated() {
+                entry.outdated += 1;
+            }
+        }
+        stats.into_values().collect()
+    }
+
+    /// Build asynchronous stream to fetch packages not checked within their check interval,
+    /// or within `max_age` for packages without one.
     fn timed_stream(
         pool: &SqlitePool,
+        max_age: Duration,
     ) -> Pin<Box<dyn Stream<Item = Result<Self, SqlxError>> + Send + '_>> {
-        let two_hours_ago = OffsetDateTime::now_utc() - Duration::from_secs(7200);
-        // macro error: cannot return value referencing local variable `two_hours_ago`
+        let max_age = i64::try_from(max_age.as_secs()).unwrap_or(i64::MAX);
+        // macro error: cannot return value referencing local variable `max_age`
         query_as(
-            "SELECT id, distname, master_site, version, local_version, last_check \
-            FROM package WHERE last_check <= $1 ORDER BY distname",
+            "SELECT id, distname, master_site, version, local_version, last_check, \
+            check_interval, source, last_error, last_error_at, stable_only, held, pattern, json_path, etag, last_modified, scan_text, mirrors, all_releases, selector, descend, semver, ignore, pinned, tag_prefix, tag_suffix, epoch \
+            FROM package \
+            WHERE NOT held \
+            AND unixepoch(last_check) + COALESCE(check_interval, $1) <= unixepoch('now') \
+            ORDER BY distname",
         )
-        .bind(two_hours_ago)
+        .bind(max_age)
         .fetch(pool)
     }
 
@@ -142,88 +699,169 @@ impl Package {
     ) -> Pin<Box<dyn Stream<Item = Result<Self, SqlxError>> + Send + '_>> {
         query_as!(
             Self,
-            "SELECT id, distname \"distname!\", master_site \"master_site!\", version \"version!\", local_version, last_check \
+            "SELECT id \"id!\", distname \"distname!\", master_site \"master_site!\", version \"version!\", local_version, last_check, check_interval, source, last_error, last_error_at, stable_only, held, pattern, json_path, etag, last_modified, scan_text, mirrors, all_releases, selector, descend, semver, ignore, pinned, tag_prefix, tag_suffix, epoch \
             FROM package ORDER BY distname"
         ).fetch(pool)
     }
 
-    /// Switch to newer PyPI URL.
-    async fn fix_pypi(&mut self, pool: &SqlitePool) -> Result<bool, SqlxError> {
-        let mut update = false;
-
-        if self.master_site.ends_with('/') {
-            update = true;
-            self.master_site.pop();
-        }
-
-        if self.master_site.contains("pypi.python.org/pypi/") {
-            update = true;
-            self.master_site = self
-                .master_site
-                .replace("pypi.python.org/pypi/", "pypi.org/project/");
+    /// Master site with every rule of `MASTER_SITE_REWRITES` applied in order, and whether
+    /// any of them changed it.
+    #[must_use]
+    pub fn normalize_master_site(&self) -> (String, bool) {
+        let mut changed = false;
+        let mut master_site = self.master_site.clone();
+        for rewrite in MASTER_SITE_REWRITES {
+            if let Some(rewritten) = rewrite(&master_site) {
+                changed = true;
+                master_site = rewritten;
+            }
         }
+        (master_site, changed)
+    }
 
-        if update {
-            query!(
-                "UPDATE package SET master_site = $2 WHERE id = $1",
-                self.id,
-                self.master_site
-            )
-            .execute(pool)
-            .await?;
+    /// Normalize master site, and store it if changed.
+    async fn fix_master_site(&mut self, pool: &SqlitePool) -> Result<bool, SqlxError> {
+        let (master_site, changed) = self.normalize_master_site();
+        if changed {
+            self.set_master_site(pool, master_site).await?;
         }
-
-        Ok(update)
+        Ok(changed)
     }
 
-    /// Store version and last check
-    pub async fn store_version(&mut self, pool: &SqlitePool) -> Result<(), SqlxError> {
+    /// Store version and last check, and record change from `old_version` in history.
+    pub async fn store_version(
+        &mut self,
+        pool: &SqlitePool,
+        old_version: &str,
+    ) -> Result<(), SqlxError> {
         self.last_check = OffsetDateTime::now_utc();
+        let mut tx = pool.begin().await?;
 
+        self.last_error = None;
+        self.last_error_at = None;
         query!(
-            "UPDATE package SET version = $2, last_check = $3 WHERE id = $1",
+            "UPDATE package SET version = $2, last_check = $3, last_error = NULL, \
+            last_error_at = NULL, etag = $4, last_modified = $5 WHERE id = $1",
             self.id,
             self.version,
             self.last_check,
+            self.etag,
+            self.last_modified,
         )
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
-        Ok(())
+        if old_version != self.version {
+            query!(
+                "INSERT INTO version_history (package_id, old_version, new_version, observed_at) \
+                VALUES ($1, $2, $3, $4)",
+                self.id,
+                old_version,
+                self.version,
+                self.last_check,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// Fetch version changes, oldest first.
+    pub async fn history(&self, pool: &SqlitePool) -> Result<Vec<VersionChange>, SqlxError> {
+        query_as!(
+            VersionChange,
+            "SELECT old_version, new_version, observed_at FROM version_history \
+            WHERE package_id = $1 ORDER BY observed_at, id",
+            self.id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Fetch up to `limit` latest version changes of all packages, newest first.
+    pub async fn recent_changes(
+        pool: &SqlitePool,
+        limit: i64,
+    ) -> Result<Vec<PackageChange>, SqlxError> {
+        query_as!(
+            PackageChange,
+            "SELECT distname, master_site, old_version, new_version, observed_at \
+            FROM version_history JOIN package ON package.id = version_history.package_id \
+            ORDER BY observed_at DESC, version_history.id DESC LIMIT $1",
+            limit
+        )
+        .fetch_all(pool)
+        .await
     }
 
     /// Mark as latest (verion and local version are the same).
-    pub async fn mark_latest(&mut self, pool: &SqlitePool) -> Result<(), SqlxError> {
-        if let Some(local_version) = &self.local_version {
-            if local_version == &self.version {
-                println!(
-                    "Package {} already has latest version {}",
-                    self.distname, local_version
-                );
-                return Ok(());
-            }
+    pub async fn mark_latest(&mut self, pool: &SqlitePool) -> Result<MarkOutcome, SqlxError> {
+        if self.local_version.as_ref() == Some(&self.version) {
+            return Ok(MarkOutcome::AlreadyLatest);
         }
 
+        let mut tx = pool.begin().await?;
         query!(
             "UPDATE package SET local_version = $2, last_check = $3 WHERE id = $1",
             self.id,
             self.version,
             self.last_check,
         )
-        .execute(pool)
+        .execute(&mut *tx)
+        .await?;
+        let marked_at = OffsetDateTime::now_utc();
+        query!(
+            "INSERT INTO mark_history (package_id, old_local_version, new_local_version, \
+            marked_at) VALUES ($1, $2, $3, $4)",
+            self.id,
+            self.local_version,
+            self.version,
+            marked_at,
+        )
+        .execute(&mut *tx)
         .await?;
+        tx.commit().await?;
 
-        if let Some(local_version) = &self.local_version {
-            println!(
-                "Package {} updated from {} to {}",
-                self.distname, local_version, self.version
-            );
-        } else {
-            println!("Package {} version set to {}", self.distname, self.version);
+        let old_local_version = self.local_version.replace(self.version.clone());
+        Ok(MarkOutcome::Marked(old_local_version))
+    }
+
+    /// Revert the last `mark_latest`, restoring the local version it replaced. Refuses if
+    /// there is no mark, or local version has changed since.
+    pub async fn unmark(&mut self, pool: &SqlitePool) -> Result<UnmarkOutcome, SqlxError> {
+        let mark = query!(
+            "SELECT id, old_local_version, new_local_version FROM mark_history \
+            WHERE package_id = $1 ORDER BY id DESC LIMIT 1",
+            self.id
+        )
+        .fetch_optional(pool)
+        .await?;
+        let Some(mark) = mark else {
+            return Ok(UnmarkOutcome::NotMarked);
+        };
+        if self.local_version.as_ref() != Some(&mark.new_local_version) {
+            return Ok(UnmarkOutcome::Changed(mark.new_local_version));
         }
-        self.local_version = Some(self.version.clone());
 
-        Ok(())
+        let mut tx = pool.begin().await?;
+        query!(
+            "UPDATE package SET local_version = $2 WHERE id = $1",
+            self.id,
+            mark.old_local_version,
+        )
+        .execute(&mut *tx)
+        .await?;
+        query!("DELETE FROM mark_history WHERE id = $1", mark.id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        self.local_version = mark.old_local_version.clone();
+        Ok(UnmarkOutcome::Reverted(
+            mark.new_local_version,
+            mark.old_local_version,
+        ))
     }
 
     // pub async fn set_local_version(
@@ -245,236 +883,978 @@ impl Package {
     //     Ok(())
     // }
 
-    /// Update last check
-    pub async fn update_last_check(&mut self, pool: &SqlitePool) -> Result<(), SqlxError> {
-        self.last_check = OffsetDateTime::now_utc();
-
+    /// Set both upstream and local version, like for a package just installed.
+    pub async fn set_version<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        version: String,
+    ) -> Result<(), SqlxError> {
         query!(
-            "UPDATE package SET last_check = $3 WHERE id = $1",
+            "UPDATE package SET version = $2, local_version = $2 WHERE id = $1",
             self.id,
-            self.last_check,
+            version
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
 
+        self.local_version = Some(version.clone());
+        self.version = version;
+
         Ok(())
     }
 
-    /// Forget about this package
-    pub async fn delete(self, pool: &SqlitePool) -> Result<(), SqlxError> {
-        query!("DELETE FROM package WHERE id = $1", self.id,)
-            .execute(pool)
-            .await?;
+    /// Change package name. Fails with unique violation if the name is taken.
+    pub async fn rename<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        distname: String,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET distname = $2 WHERE id = $1",
+            self.id,
+            distname
+        )
+        .execute(executor)
+        .await?;
 
-        println!("Removed {}", self.distname);
+        self.distname = distname;
 
         Ok(())
     }
 
-    /// Use while
-    pub async fn stream_from_db(pool: &SqlitePool) {
-        while let Some(pkg) = Self::stream(pool).next().await {
-            if let Ok(pkg) = pkg {
-                if !pkg.is_latest() {
-                    println!("{pkg}");
-                }
-            }
-        }
-    }
+    /// Set URL of the project page.
+    pub async fn set_master_site<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        master_site: String,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET master_site = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            master_site
+        )
+        .execute(executor)
+        .await?;
 
-    /// Use for_each_concurrent()
-    pub async fn info_stream(pool: &SqlitePool) {
-        Self::stream(pool)
-            .for_each_concurrent(10, |pkg| async move {
-                if let Ok(pkg) = pkg {
-                    if !pkg.is_latest() {
-                        println!("{pkg}");
-                    }
-                }
-            })
-            .await;
-    }
+        self.master_site = master_site;
+        self.forget_validators();
 
-    pub async fn check_all(
-        pool: &SqlitePool,
-        github_account: Option<&String>,
-        github_token: Option<&String>,
-    ) {
-        Self::timed_stream(pool)
-            .for_each_concurrent(10, |pkg| async move {
-                if let Ok(mut pkg) = pkg {
-                    pkg.fix_pypi(pool).await.unwrap();
-                    if pkg.auto_check(github_account, github_token).await {
-                        pkg.store_version(pool).await.unwrap();
-                    } else {
-                        pkg.update_last_check(pool).await.unwrap();
-                    }
-                }
-            })
-            .await;
+        Ok(())
     }
 
-    fn parse_pypi(&mut self, pypi_project: PypiProject) -> bool {
-        if self.version == pypi_project.info.version {
-            false
-        } else {
-            println!(
-                "{} {} -> {}",
-                self.distname,
-                self.local_version.as_deref().unwrap_or("-"),
-                pypi_project.info.version
-            );
-            self.version = pypi_project.info.version;
-            true
-        }
-    }
+    /// Set whether pre-release versions are ignored.
+    pub async fn set_stable_only<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        stable_only: bool,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET stable_only = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            stable_only,
+        )
+        .execute(executor)
+        .await?;
 
-    fn parse_github(&mut self, github_info: &GitHubReleaseInfo) -> bool {
-        let version = github_info
-            .tag_name
-            .trim_start_matches(|c| !char::is_ascii_digit(&c));
-        if self.version != version {
-            println!(
-                "{} {} -> {}",
-                self.distname,
-                self.local_version.as_deref().unwrap_or("-"),
-                version
-            );
-            self.version = version.into();
-            true
-        } else {
-            false
-        }
+        self.stable_only = stable_only;
+        self.forget_validators();
+
+        Ok(())
     }
 
-    pub async fn auto_check(
+    /// Hold at local version, or release the hold.
+    pub async fn set_held<'e, E: SqliteExecutor<'e>>(
         &mut self,
-        github_account: Option<&String>,
-        github_token: Option<&String>,
-    ) -> bool {
-        let mut headers = header::HeaderMap::new();
-        headers.insert(
-            header::ACCEPT,
-            header::HeaderValue::from_static("application/json"),
-        );
-        headers.insert(
-            header::USER_AGENT,
-            header::HeaderValue::from_static("Version-Tracker"),
-        );
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()
-            .unwrap();
+        executor: E,
+        held: bool,
+    ) -> Result<(), SqlxError> {
+        query!("UPDATE package SET held = $2 WHERE id = $1", self.id, held)
+            .execute(executor)
+            .await?;
 
-        let url = Url::parse(&self.master_site).unwrap();
-        if let Some(hostname) = url.domain() {
-            match hostname {
-                "pypi.org" => {
-                    if let Some(project) = url.path_segments().and_then(Iterator::last) {
-                        let response = client
-                            .get(format!("https://pypi.org/pypi/{project}/json"))
-                            .send()
-                            .await
-                            .unwrap();
-                        if response.status() != StatusCode::OK {
-                            eprintln!("Status {}", response.status());
-                            // eprintln!("Text: {:?}", response.text().await.unwrap());
-                            return false;
-                        }
-                        match response.json::<PypiProject>().await {
-                            Ok(pypi_project) => {
-                                return self.parse_pypi(pypi_project);
-                            }
-                            Err(err) => {
-                                println!(
-                                    "JSON error for {} [{}]: {}",
-                                    self.distname, self.master_site, err
-                                );
-                            }
-                        }
-                    }
-                }
-                // https://docs.github.com/en/rest/releases/releases#get-the-latest-release
-                // TODO: Accept: application/vnd.github.v3+json
-                "github.com" => {
-                    let path =
-                        format!("https://api.github.com/repos{}/releases/latest", url.path());
-                    let mut request = client.get(path);
-                    if let Some(account) = github_account {
-                        // Token (classic) with "read:project" access
-                        request = request.basic_auth(account, github_token);
-                    }
-                    let response = request.send().await.unwrap();
-                    if response.status() != StatusCode::OK {
-                        eprintln!("Status {}", response.status());
-                        // eprintln!("Text: {:?}", response.text().await.unwrap());
-                        return false;
-                    }
-                    match response.json::<GitHubReleaseInfo>().await {
-                        Ok(github_info) => {
-                            return self.parse_github(&github_info);
-                        }
-                        Err(err) => {
-                            eprintln!(
-                                "JSON error for {} [{}]: {}",
-                                self.distname, self.master_site, err
-                            );
-                        }
-                    }
-                }
-                _ => match client.get(&self.master_site).send().await {
-                    Ok(response) => {
-                        if response.status() != StatusCode::OK {
-                            eprintln!("Status {}", response.status());
-                            return false;
-                        }
-                        let body = response.text().await.unwrap();
-                        match parse_html(&body) {
-                            None => eprintln!("No version for {}", self.distname),
-                            Some(version) => {
-                                let my_version = Version::from_str(&self.version).unwrap();
-                                if my_version < version {
-                                    self.version = version.to_string();
-                                }
-                            }
-                        }
-                    }
-                    Err(err) => eprintln!("Error fetching {}: {}", self.distname, err),
-                },
-            }
-        }
-        false
-    }
+        self.held = held;
 
-    #[must_use]
-    pub fn is_latest(&self) -> bool {
-        if let Some(local) = &self.local_version {
-            let local: Vec<i32> = local
-                .split('.')
-                .map_while(|d| i32::from_str(d).ok())
-                .collect();
-            let version = self
-                .version
-                .split('.')
-                .map_while(|d| i32::from_str(d).ok())
-                .collect();
-            local >= version
-        } else {
-            false
-        }
+        Ok(())
     }
 
-    pub fn display_info(&self) {
-        println!("Distname:      {}", self.distname);
-        println!("Master site:   {}", self.master_site);
-        println!("Version:       {}", self.version);
-        println!(
-            "Local version: {}",
-            self.local_version.as_ref().unwrap_or(&"-".into())
-        );
-        println!("Last check:    {}", self.last_check);
-    }
-}
+    /// Set whether versions are compared as semantic versions.
+    pub async fn set_semver<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        semver: bool,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET semver = $2 WHERE id = $1",
+            self.id,
+            semver
+        )
+        .execute(executor)
+        .await?;
+
+        self.semver = semver;
+
+        Ok(())
+    }
+
+    /// Pin at version, or remove the pin.
+    pub async fn set_pinned<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        pinned: Option<String>,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET pinned = $2 WHERE id = $1",
+            self.id,
+            pinned
+        )
+        .execute(executor)
+        .await?;
+
+        self.pinned = pinned;
+
+        Ok(())
+    }
+
+    /// Set regular expression extracting version from HTML page; `None` means looking at links.
+    pub async fn set_pattern<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        pattern: Option<String>,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET pattern = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            pattern
+        )
+        .execute(executor)
+        .await?;
+
+        self.pattern = pattern;
+        self.forget_validators();
+
+        Ok(())
+    }
+
+    /// Set CSS selector of HTML page parts looked at for versions. Cached validators are
+    /// dropped, so the page is parsed again.
+    pub async fn set_selector<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        selector: Option<String>,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET selector = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            selector
+        )
+        .execute(executor)
+        .await?;
+
+        self.selector = selector;
+        self.forget_validators();
+
+        Ok(())
+    }
+
+    /// Set regular expression of upstream versions to skip. Cached validators are dropped, so
+    /// versions skipped before are looked at again.
+    pub async fn set_ignore<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        ignore: Option<String>,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET ignore = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            ignore
+        )
+        .execute(executor)
+        .await?;
+
+        self.ignore = ignore;
+        self.forget_validators();
+
+        Ok(())
+    }
+
+    /// Set prefix of release tags, stripped to get version.
+    pub async fn set_tag_prefix<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        tag_prefix: Option<String>,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET tag_prefix = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            tag_prefix
+        )
+        .execute(executor)
+        .await?;
+
+        self.tag_prefix = tag_prefix;
+        self.forget_validators();
+
+        Ok(())
+    }
+
+    /// Set suffix of release tags, stripped to get version.
+    pub async fn set_tag_suffix<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        tag_suffix: Option<String>,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET tag_suffix = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            tag_suffix
+        )
+        .execute(executor)
+        .await?;
+
+        self.tag_suffix = tag_suffix;
+        self.forget_validators();
+
+        Ok(())
+    }
+
+    /// Set epoch of versions found upstream, or remove it.
+    pub async fn set_epoch<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        epoch: Option<i64>,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET epoch = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            epoch
+        )
+        .execute(executor)
+        .await?;
+
+        self.epoch = epoch;
+        self.forget_validators();
+
+        Ok(())
+    }
+
+    /// Set whether versions are looked for in text of HTML page too.
+    pub async fn set_scan_text<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        scan_text: bool,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET scan_text = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            scan_text,
+        )
+        .execute(executor)
+        .await?;
+
+        self.scan_text = scan_text;
+        self.forget_validators();
+
+        Ok(())
+    }
+
+    /// Set whether versions are looked for in the highest version subdirectory of HTML
+    /// directory listing.
+    pub async fn set_descend<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        descend: bool,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET descend = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            descend,
+        )
+        .execute(executor)
+        .await?;
+
+        self.descend = descend;
+        self.forget_validators();
+
+        Ok(())
+    }
+
+    /// Set whether all GitHub releases are looked at, not only the latest one.
+    pub async fn set_all_releases<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        all_releases: bool,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET all_releases = $2 WHERE id = $1",
+            self.id,
+            all_releases,
+        )
+        .execute(executor)
+        .await?;
+
+        self.all_releases = all_releases;
+
+        Ok(())
+    }
+
+    /// Set fallback master sites, tried in order.
+    pub async fn set_mirrors<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        mirrors: Vec<String>,
+    ) -> Result<(), SqlxError> {
+        let mirrors = mirrors.join("\n");
+        query!(
+            "UPDATE package SET mirrors = $2 WHERE id = $1",
+            self.id,
+            mirrors,
+        )
+        .execute(executor)
+        .await?;
+
+        self.mirrors = mirrors;
+
+        Ok(())
+    }
+
+    /// Set path of version field in JSON document.
+    pub async fn set_json_path<'e, E: SqliteExecutor<'e>>(
+        &mut self,
+        executor: E,
+        json_path: Option<String>,
+    ) -> Result<(), SqlxError> {
+        query!(
+            "UPDATE package SET json_path = $2, etag = NULL, last_modified = NULL WHERE id = $1",
+            self.id,
+            json_path
+        )
+        .execute(executor)
+        .await?;
+
+        self.json_path = json_path;
+        self.forget_validators();
+
+        Ok(())
+    }
+
+    /// Update last check, and clear error of the previous one.
+    pub async fn update_last_check(&mut self, pool: &SqlitePool) -> Result<(), SqlxError> {
+        self.store_error(pool, None).await
+    }
+
+    /// Update last check, and store its error or clear previous one.
+    pub async fn store_error(
+        &mut self,
+        pool: &SqlitePool,
+        error: Option<String>,
+    ) -> Result<(), SqlxError> {
+        self.last_check = OffsetDateTime::now_utc();
+        self.last_error_at = error.as_ref().map(|_| self.last_check);
+        self.last_error = error;
+
+        query!(
+            "UPDATE package SET last_check = $2, last_error = $3, last_error_at = $4, etag = $5, \
+            last_modified = $6 WHERE id = $1",
+            self.id,
+            self.last_check,
+            self.last_error,
+            self.last_error_at,
+            self.etag,
+            self.last_modified,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Forget about this package
+    pub async fn delete(self, pool: &SqlitePool) -> Result<(), SqlxError> {
+        query!("DELETE FROM package WHERE id = $1", self.id,)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stream outdated packages, skipping rows which fail to load.
+    pub fn outdated_stream(pool: &SqlitePool) -> impl Stream<Item = Self> + '_ {
+        Self::stream(pool).filter_map(|pkg| ready(pkg.ok().filter(Self::is_outdated)))
+    }
+
+    /// Check for new version and store the result; with `dry_run`, nothing is stored and
+    /// webhook is not notified.
+    /// Check failure is reported in the result, and does not fail the call.
+    /// With `redirect_client`, master site is switched to where it permanently redirects to.
+    pub async fn check(
+        &mut self,
+        pool: &SqlitePool,
+        client: &Client,
+        redirect_client: Option<&Client>,
+        config: &Config,
+        dry_run: bool,
+    ) -> Result<CheckResult, SqlxError> {
+        if dry_run {
+            self.master_site = self.normalize_master_site().0;
+        } else {
+            self.fix_master_site(pool).await?;
+        }
+        let old_version = self.version.clone();
+        let mut notes = Vec::new();
+        let (updated, error) = match self.auto_check(client, config, &mut notes).await {
+            Ok(updated) => (updated, None),
+            Err(err) => (false, Some(err.to_string())),
+        };
+        if !dry_run {
+            match &error {
+                Some(error) => {
+                    // response may be fine next time, and must be parsed then
+                    self.forget_validators();
+                    self.store_error(pool, Some(error.clone())).await?;
+                }
+                None if updated => self.store_version(pool, &old_version).await?,
+                None => self.update_last_check(pool).await?,
+            }
+            if let (None, Some(redirect_client)) = (&error, redirect_client) {
+                self.follow_redirects(pool, redirect_client, config, &mut notes)
+                    .await?;
+            }
+        }
+        if let (Some(pinned), true) = (&self.pinned, updated) {
+            notes.push(format!(
+                "{} is pinned at {pinned}, upstream version is {}",
+                self.distname, self.version
+            ));
+        }
+        let mut result = CheckResult {
+            distname: self.distname.clone(),
+            master_site: self.master_site.clone(),
+            local_version: self.local_version.clone(),
+            old_version,
+            version: self.version.clone(),
+            updated,
+            error,
+            notes,
+        };
+        // pinned package stays where it is, so there is nothing to act on
+        if updated && !dry_run && self.pinned.is_none() {
+            if let Some(url) = &config.webhook.url {
+                if let Err(err) = post_webhook(client, url, &result, config).await {
+                    result
+                        .notes
+                        .push(format!("Webhook error for {}: {}", self.distname, err));
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Switch to the location master site permanently redirects to.
+    async fn follow_redirects(
+        &mut self,
+        pool: &SqlitePool,
+        client: &Client,
+        config: &Config,
+        notes: &mut Vec<String>,
+    ) -> Result<(), SqlxError> {
+        // git repository URL can't be requested as it is, and bare names, like of npm
+        // packages, or FTP sites have no redirects
+        if Source::from_str(&self.source) == Ok(Source::Git)
+            || !Url::parse(&self.master_site)
+                .is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+        {
+            return Ok(());
+        }
+        match permanent_location(client, &self.master_site, &config.http).await {
+            Ok(Some(location)) if location != self.master_site => {
+                notes.push(format!(
+                    "Master site of {} moved: {} -> {}",
+                    self.distname, self.master_site, location
+                ));
+                self.set_master_site(pool, location).await?;
+            }
+            Ok(_) => (),
+            Err(err) => notes.push(format!("Redirect error for {}: {}", self.distname, err)),
+        }
+        Ok(())
+    }
+
+    /// Check packages not checked within `max_age`, or all packages if `max_age` is `None`.
+    /// Up to `check.concurrency` packages are checked at the same time, and results are
+    /// yielded as soon as each check is done. With `dry_run`, results are not stored.
+    pub fn check_all<'a>(
+        pool: &'a SqlitePool,
+        client: &'a Client,
+        redirect_client: Option<&'a Client>,
+        config: &'a Config,
+        max_age: Option<Duration>,
+        dry_run: bool,
+    ) -> impl Stream<Item = CheckResult> + 'a {
+        let stream = match max_age {
+            Some(max_age) => Self::timed_stream(pool, max_age),
+            None => Self::stream(pool),
+        };
+        stream
+            .filter_map(|pkg| ready(pkg.ok().filter(|pkg| !pkg.held)))
+            .map(move |mut pkg| async move {
+                let old_version = pkg.version.clone();
+                match pkg
+                    .check(pool, client, redirect_client, config, dry_run)
+                    .await
+                {
+                    Ok(result) => result,
+                    // database failure of one package doesn't stop checking the others
+                    Err(err) => CheckResult {
+                        distname: pkg.distname,
+                        master_site: pkg.master_site,
+                        local_version: pkg.local_version,
+                        old_version,
+                        version: pkg.version,
+                        updated: false,
+                        error: Some(format!("database error: {err}")),
+                        notes: Vec::new(),
+                    },
+                }
+            })
+            .buffer_unordered(config.check.concurrency.max(1))
+    }
+
+    /// Fetch packages whose last check failed.
+    pub async fn failed(pool: &SqlitePool) -> Result<Vec<Self>, SqlxError> {
+        query_as!(
+            Self,
+            "SELECT id \"id!\", distname \"distname!\", master_site \"master_site!\", version \"version!\", local_version, last_check, check_interval, source, last_error, last_error_at, stable_only, held, pattern, json_path, etag, last_modified, scan_text, mirrors, all_releases, selector, descend, semver, ignore, pinned, tag_prefix, tag_suffix, epoch \
+            FROM package WHERE last_error IS NOT NULL ORDER BY distname",
+        ).fetch_all(pool).await
+    }
+
+    /// Fetch packages with newer upstream version, except held ones.
+    pub async fn outdated(pool: &SqlitePool) -> Result<Vec<Self>, SqlxError> {
+        Self::stream(pool)
+            .try_filter(|pkg| ready(pkg.is_outdated()))
+            .try_collect()
+            .await
+    }
+
+    /// Drop cached response validators, so the next check fetches the whole document.
+    fn forget_validators(&mut self) {
+        self.etag = None;
+        self.last_modified = None;
+    }
+
+    /// Compare with upstream version, given the package epoch; store it if newer. Replacing
+    /// a known version which is no version is noted.
+    fn parse_version(&mut self, version: &str, notes: &mut Vec<String>) -> bool {
+        let version = epoch_release(version, self.epoch());
+        if self.is_newer(&version) {
+            if Version::from_str(&self.version).is_err() {
+                notes.push(format!(
+                    "Invalid version {} of {}, replaced with {}",
+                    self.version, self.distname, version
+                ));
+            }
+            self.version = version;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Upstream version is newer than the one known, as semantic version if set. Any other
+    /// version replaces a known one which is no version.
+    fn is_newer(&self, version: &str) -> bool {
+        if self.semver {
+            if let (Ok(known), Ok(version)) =
+                (SemVer::from_str(&self.version), SemVer::from_str(version))
+            {
+                return version > known;
+            }
+        }
+        match (Version::from_str(&self.version), Version::from_str(version)) {
+            (Ok(known), Ok(version)) => version > known,
+            (Ok(_), Err(_)) => false,
+            (Err(_), _) => self.version != version,
+        }
+    }
+
+    /// Compare with a release tag, already without tag prefix and suffix, ignoring any
+    /// leading text like `v` or `release-`.
+    fn parse_tag(&mut self, tag_name: &str, notes: &mut Vec<String>) -> bool {
+        self.parse_version(&tag_release(tag_name), notes)
+    }
+
+    /// Epoch of versions found upstream; 0 means none.
+    fn epoch(&self) -> i32 {
+        self.epoch
+            .map_or(0, |epoch| i32::try_from(epoch).unwrap_or(i32::MAX))
+    }
+
+    /// Look for upstream version, and keep it if newer. Returns whether version changed.
+    /// Remarks, like no suitable version found, are added to `notes`.
+    /// If master site fails or has no version, mirrors are tried in order until one has a
+    /// version; it is noted if newer.
+    pub async fn auto_check(
+        &mut self,
+        client: &Client,
+        config: &Config,
+        notes: &mut Vec<String>,
+    ) -> Result<bool, CheckError> {
+        let custom;
+        let backend: &dyn Backend = match Source::from_str(&self.source) {
+            Ok(source) => source.backend(),
+            // not built in, so defined in configuration
+            Err(err) => {
+                custom = Custom(
+                    config
+                        .sources
+                        .get(&self.source)
+                        .ok_or(CheckError::Source(err))?,
+                );
+                &custom
+            }
+        };
+        self.auto_check_with(backend, client, config, notes).await
+    }
+
+    /// Same as `auto_check`, but look for upstream version with `backend` instead of the
+    /// one of package source.
+    pub async fn auto_check_with(
+        &mut self,
+        backend: &dyn Backend,
+        client: &Client,
+        config: &Config,
+        notes: &mut Vec<String>,
+    ) -> Result<bool, CheckError> {
+        let master_site = self.master_site.clone();
+        let result = self
+            .check_site(backend, &master_site, client, config, notes)
+            .await;
+        match result {
+            Ok(SiteCheck::Found(updated)) => return Ok(updated),
+            Ok(SiteCheck::NotModified) => return Ok(false),
+            Ok(SiteCheck::Nothing) | Err(_) => (),
+        }
+        let mirrors: Vec<String> = self.mirrors().map(Into::into).collect();
+        for mirror in mirrors {
+            // validators belong to master site
+            self.forget_validators();
+            match self
+                .check_site(backend, &mirror, client, config, notes)
+                .await
+            {
+                Ok(SiteCheck::Found(updated)) => {
+                    if updated {
+                        notes.push(format!(
+                            "Version of {} found at mirror {mirror}",
+                            self.distname
+                        ));
+                    }
+                    self.forget_validators();
+                    return Ok(updated);
+                }
+                Ok(_) => (),
+                Err(err) => notes.push(format!(
+                    "Mirror {mirror} of {} failed: {err}",
+                    self.distname
+                )),
+            }
+        }
+        result.map(|_| false)
+    }
+
+    /// Look for upstream version at `site`, master site or one of its mirrors.
+    async fn check_site(
+        &mut self,
+        backend: &dyn Backend,
+        site: &str,
+        client: &Client,
+        config: &Config,
+        notes: &mut Vec<String>,
+    ) -> Result<SiteCheck, CheckError> {
+        let ignore = self.ignore.as_deref().map(Regex::new).transpose()?;
+        let mut query = Query {
+            client,
+            config,
+            site,
+            url: backend.url(site)?,
+            distname: &self.distname,
+            version: &self.version,
+            stable_only: self.stable_only,
+            pattern: self.pattern.as_deref(),
+            json_path: self.json_path.as_deref(),
+            scan_text: self.scan_text,
+            all_releases: self.all_releases,
+            selector: self.selector.as_deref(),
+            descend: self.descend,
+            ignore: ignore.as_ref(),
+            tag_prefix: self.tag_prefix.as_deref(),
+            tag_suffix: self.tag_suffix.as_deref(),
+            epoch: self.epoch(),
+            etag: self.etag.clone(),
+            last_modified: self.last_modified.clone(),
+            notes,
+        };
+        let found = backend.latest(&mut query).await;
+        // validators are kept even if the check failed later on
+        let Query {
+            etag,
+            last_modified,
+            ..
+        } = query;
+        self.etag = etag;
+        self.last_modified = last_modified;
+        let found = found?;
+        // versions and tags as published, which backends may not have matched against it
+        if let (Some(ignore), Found::Version(version) | Found::Tag(version)) = (&ignore, &found) {
+            if ignore.is_match(version) {
+                notes.push(format!("Version {version} of {} ignored", self.distname));
+                return Ok(SiteCheck::Nothing);
+            }
+        }
+        Ok(match found {
+            Found::Version(version) => SiteCheck::Found(self.parse_version(&version, notes)),
+            Found::Tag(tag) => SiteCheck::Found(self.parse_tag(&tag, notes)),
+            Found::Nothing => SiteCheck::Nothing,
+            Found::NotModified => SiteCheck::NotModified,
+        })
+    }
+
+    /// Local version is up to date: not lower than upstream version, or than the version
+    /// package is pinned at.
+    #[must_use]
+    pub fn is_latest(&self) -> bool {
+        if let Some(local) = &self.local_version {
+            let version = self.pinned.as_deref().unwrap_or(&self.version);
+            if self.semver {
+                if let (Ok(local), Ok(version)) =
+                    (SemVer::from_str(local), SemVer::from_str(version))
+                {
+                    return local >= version;
+                }
+            }
+            match (Version::from_str(local), Version::from_str(version)) {
+                (Ok(local), Ok(version)) => local >= version,
+                _ => false,
+            }
+        } else {
+            false
+        }
+    }
+
+    #[must_use]
+    pub fn distname(&self) -> &str {
+        &self.distname
+    }
+
+    /// Latest upstream version known.
+    #[must_use]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Version installed locally, if known.
+    #[must_use]
+    pub fn local_version(&self) -> Option<&str> {
+        self.local_version.as_deref()
+    }
+
+    /// Fallback master sites, in order.
+    pub fn mirrors(&self) -> impl Iterator<Item = &str> {
+        self.mirrors.lines()
+    }
+
+    /// Why the last check failed, if it did.
+    #[must_use]
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// Local version is behind upstream version, or the one package is pinned at, and
+    /// package is not held.
+    #[must_use]
+    pub fn is_outdated(&self) -> bool {
+        !self.held && !self.is_latest()
+    }
+
+    #[must_use]
+    pub fn info(&self) -> PackageInfo<'_> {
+        PackageInfo {
+            distname: &self.distname,
+            master_site: &self.master_site,
+            version: &self.version,
+            local_version: self.local_version.as_deref(),
+            source: &self.source,
+            stable_only: self.stable_only,
+            held: self.held,
+            pattern: self.pattern.as_deref(),
+            json_path: self.json_path.as_deref(),
+            scan_text: self.scan_text,
+            mirrors: self.mirrors().collect(),
+            all_releases: self.all_releases,
+            selector: self.selector.as_deref(),
+            descend: self.descend,
+            semver: self.semver,
+            ignore: self.ignore.as_deref(),
+            pinned: self.pinned.as_deref(),
+            tag_prefix: self.tag_prefix.as_deref(),
+            tag_suffix: self.tag_suffix.as_deref(),
+            epoch: self.epoch,
+            last_check: self.last_check.format(&Rfc3339).unwrap_or_default(),
+            outdated: self.is_outdated(),
+            last_error: self.last_error.as_deref(),
+            last_error_at: self
+                .last_error_at
+                .and_then(|last_error_at| last_error_at.format(&Rfc3339).ok()),
+        }
+    }
+
+    /// All settings and check state, one per line, for display.
+    #[must_use]
+    pub fn details(&self) -> PackageDetails<'_> {
+        PackageDetails(self)
+    }
+}
+
+/// Multi-line description of a package; see `Package::details`.
+pub struct PackageDetails<'a>(&'a Package);
+
+impl fmt::Display for PackageDetails<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pkg = self.0;
+        writeln!(f, "Distname:      {}", pkg.distname)?;
+        writeln!(f, "Master site:   {}", pkg.master_site)?;
+        writeln!(f, "Version:       {}", pkg.version)?;
+        writeln!(
+            f,
+            "Local version: {}",
+            pkg.local_version.as_deref().unwrap_or("-")
+        )?;
+        writeln!(f, "Source:        {}", pkg.source)?;
+        if pkg.stable_only {
+            writeln!(f, "Stable only:   yes")?;
+        }
+        if pkg.held {
+            writeln!(f, "Held:          yes")?;
+        }
+        if let Some(pattern) = &pkg.pattern {
+            writeln!(f, "Pattern:       {pattern}")?;
+        }
+        if let Some(json_path) = &pkg.json_path {
+            writeln!(f, "JSON path:     {json_path}")?;
+        }
+        if pkg.scan_text {
+            writeln!(f, "Scan text:     yes")?;
+        }
+        for mirror in pkg.mirrors() {
+            writeln!(f, "Mirror:        {mirror}")?;
+        }
+        if pkg.all_releases {
+            writeln!(f, "All releases:  yes")?;
+        }
+        if let Some(selector) = &pkg.selector {
+            writeln!(f, "Selector:      {selector}")?;
+        }
+        if pkg.descend {
+            writeln!(f, "Descend:       yes")?;
+        }
+        if pkg.semver {
+            writeln!(f, "Semver:        yes")?;
+        }
+        if let Some(ignore) = &pkg.ignore {
+            writeln!(f, "Ignore:        {ignore}")?;
+        }
+        if let Some(pinned) = &pkg.pinned {
+            writeln!(f, "Pinned:        {pinned}")?;
+        }
+        if let Some(tag_prefix) = &pkg.tag_prefix {
+            writeln!(f, "Tag prefix:    {tag_prefix}")?;
+        }
+        if let Some(tag_suffix) = &pkg.tag_suffix {
+            writeln!(f, "Tag suffix:    {tag_suffix}")?;
+        }
+        if let Some(epoch) = pkg.epoch {
+            writeln!(f, "Epoch:         {epoch}")?;
+        }
+        writeln!(f, "Last check:    {}", pkg.last_check)?;
+        match pkg.check_interval {
+            Some(check_interval) => writeln!(f, "Check interval: {check_interval}s")?,
+            None => writeln!(f, "Check interval: -")?,
+        }
+        if let Some(last_error) = &pkg.last_error {
+            writeln!(f, "Last error:    {last_error}")?;
+            if let Some(last_error_at) = pkg.last_error_at {
+                writeln!(f, "Last error at: {last_error_at}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rules rewriting master site to its canonical form; each returns `None` if it doesn't
+/// apply.
+const MASTER_SITE_REWRITES: [fn(&str) -> Option<String>; 2] = [trim_trailing_slash, pypi_project];
+
+/// `https://example.com/project/` -> `https://example.com/project`
+fn trim_trailing_slash(url: &str) -> Option<String> {
+    url.strip_suffix('/').map(Into::into)
+}
+
+/// Legacy PyPI URL `https://pypi.python.org/pypi/name` -> `https://pypi.org/project/name`
+fn pypi_project(url: &str) -> Option<String> {
+    url.contains("pypi.python.org/pypi/")
+        .then(|| url.replace("pypi.python.org/pypi/", "pypi.org/project/"))
+}
+
+/// Longest chain of redirects followed by `permanent_location`.
+const MAX_REDIRECTS: usize = 10;
+
+/// Follow permanent redirects from `url` with `client` not following them itself, stopping
+/// at the first other response. Returns the final URL if there was any such redirect.
+/// Fragment, like branch of kernel.org, is kept unless a location has its own.
+async fn permanent_location(
+    client: &Client,
+    url: &str,
+    http: &HttpConfig,
+) -> Result<Option<String>, CheckError> {
+    let mut location = Url::parse(url)?;
+    let mut moved = false;
+    for _ in 0..MAX_REDIRECTS {
+        let response = send(client.get(location.clone()), http).await?;
+        let status = response.status();
+        if status != StatusCode::MOVED_PERMANENTLY && status != StatusCode::PERMANENT_REDIRECT {
+            break;
+        }
+        let Some(next) = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+        else {
+            break;
+        };
+        let fragment = location.fragment().map(ToString::to_string);
+        location = location.join(next)?;
+        if location.fragment().is_none() {
+            location.set_fragment(fragment.as_deref());
+        }
+        moved = true;
+    }
+    Ok(moved.then(|| location.to_string()))
+}
+
+/// Notify webhook about new version.
+async fn post_webhook(
+    client: &Client,
+    url: &str,
+    result: &CheckResult,
+    config: &Config,
+) -> Result<(), CheckError> {
+    let response = send(client.post(url).json(result), &config.http).await?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(CheckError::Status(response.status()))
+    }
+}
 
 impl fmt::Display for Package {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -487,3 +1867,813 @@ impl fmt::Display for Package {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{http_client, redirect_client};
+    use futures::future::BoxFuture;
+
+    fn package(version: &str, local_version: Option<&str>) -> Package {
+        Package {
+            id: 0,
+            distname: "test".into(),
+            master_site: "https://example.com/".into(),
+            version: version.into(),
+            local_version: local_version.map(Into::into),
+            last_check: OffsetDateTime::now_utc(),
+            check_interval: None,
+            source: "html".into(),
+            last_error: None,
+            last_error_at: None,
+            stable_only: false,
+            held: false,
+            pattern: None,
+            json_path: None,
+            etag: None,
+            last_modified: None,
+            scan_text: false,
+            mirrors: String::new(),
+            all_releases: false,
+            selector: None,
+            descend: false,
+            semver: false,
+            ignore: None,
+            pinned: None,
+            tag_prefix: None,
+            tag_suffix: None,
+            epoch: None,
+        }
+    }
+
+    #[test]
+    fn test_trim_trailing_slash() {
+        assert_eq!(
+            trim_trailing_slash("https://example.com/project/").as_deref(),
+            Some("https://example.com/project")
+        );
+        assert_eq!(trim_trailing_slash("https://example.com/project"), None);
+    }
+
+    #[test]
+    fn test_pypi_project() {
+        assert_eq!(
+            pypi_project("https://pypi.python.org/pypi/requests").as_deref(),
+            Some("https://pypi.org/project/requests")
+        );
+        assert_eq!(pypi_project("https://pypi.org/project/requests"), None);
+        assert_eq!(pypi_project("https://example.com/pypi/requests"), None);
+    }
+
+    #[test]
+    fn test_normalize_master_site() {
+        let mut pkg = package("1.0", None);
+        pkg.master_site = "https://pypi.python.org/pypi/requests/".into();
+        assert_eq!(
+            pkg.normalize_master_site(),
+            ("https://pypi.org/project/requests".into(), true)
+        );
+        pkg.master_site = "https://pypi.org/project/requests".into();
+        assert_eq!(
+            pkg.normalize_master_site(),
+            ("https://pypi.org/project/requests".into(), false)
+        );
+    }
+
+    #[test]
+    fn test_is_latest() {
+        assert!(!package("1.2", None).is_latest());
+        assert!(package("1.2", Some("1.2.0")).is_latest());
+        assert!(package("1.2.0", Some("1.2")).is_latest());
+        assert!(package("1.2.3", Some("1.2.3")).is_latest());
+        assert!(!package("1.2.4", Some("1.2.3")).is_latest());
+        assert!(package("2024.5.1", Some("20240501")).is_latest());
+        assert!(!package("2024.05.02", Some("20240501")).is_latest());
+
+        let mut pkg = package("3.0.1", Some("2.4.9"));
+        pkg.pinned = Some("2.4.9".into());
+        assert!(pkg.is_latest());
+        assert!(!pkg.is_outdated());
+        pkg.pinned = Some("2.4.10".into());
+        assert!(pkg.is_outdated());
+
+        // alpha.beta follows alpha.1 in semver
+        let mut pkg = package("1.0.0-alpha.1", Some("1.0.0-alpha.beta"));
+        assert!(!pkg.is_latest());
+        pkg.semver = true;
+        assert!(pkg.is_latest());
+        pkg.version = "1.0.0-rc.1+build.2".into();
+        assert!(!pkg.is_latest());
+        pkg.local_version = Some("1.0.0-rc.1".into());
+        assert!(pkg.is_latest());
+        assert!(!package("1.8.10p1", Some("1.8.10")).is_latest());
+        assert!(package("1.8.10", Some("1.8.10p1")).is_latest());
+        assert!(!package("1.2.3", Some("latest")).is_latest());
+    }
+
+    #[test]
+    fn test_is_outdated() {
+        let mut pkg = package("1.2.4", Some("1.2.3"));
+        assert!(pkg.is_outdated());
+        pkg.held = true;
+        assert!(!pkg.is_outdated());
+        assert!(!package("1.2.3", Some("1.2.3")).is_outdated());
+    }
+
+    #[test]
+    fn test_sort_by_version() {
+        let mut pkgs = vec![
+            package("1.10.0", None),
+            package("1.9.0", None),
+            package("1.2.0", None),
+        ];
+        Package::sort_by_version(&mut pkgs);
+        let versions: Vec<&str> = pkgs.iter().map(|pkg| pkg.version.as_str()).collect();
+        assert_eq!(versions, ["1.2.0", "1.9.0", "1.10.0"]);
+    }
+
+    #[test]
+    fn test_parse_tag() {
+        let mut notes = Vec::new();
+        let mut pkg = package("1.6", None);
+        assert!(pkg.parse_tag("jq-1.7.1", &mut notes));
+        assert_eq!(pkg.version, "1.7.1");
+        assert!(!pkg.parse_tag("v1.7.1", &mut notes));
+        pkg.parse_tag("python3-bindings-3.1", &mut notes);
+        assert_eq!(pkg.version, "3.1");
+        pkg.parse_tag("release-2024-05", &mut notes);
+        assert_eq!(pkg.version, "2024.05");
+    }
+
+    #[test]
+    fn test_parse_version() {
+        let mut notes = Vec::new();
+        let mut pkg = package("1.2", None);
+        assert!(!pkg.parse_version("1.2.0", &mut notes));
+        // backport released after
+        assert!(!pkg.parse_version("1.1.9", &mut notes));
+        assert!(!pkg.parse_version("latest", &mut notes));
+        assert_eq!(pkg.version, "1.2");
+        assert!(pkg.parse_version("1.3", &mut notes));
+        assert_eq!(pkg.version, "1.3");
+        assert!(notes.is_empty());
+        pkg.version = "unknown".into();
+        assert!(pkg.parse_version("1.3", &mut notes));
+        assert_eq!(pkg.version, "1.3");
+        assert_eq!(
+            notes,
+            ["Invalid version unknown of test, replaced with 1.3"]
+        );
+        // alpha.beta follows alpha.1 in semver
+        pkg.version = "1.0.0-alpha.1".into();
+        pkg.semver = true;
+        assert!(pkg.parse_version("1.0.0-alpha.beta", &mut notes));
+        assert!(!pkg.parse_version("1.0.0-alpha.2", &mut notes));
+    }
+
+    #[test]
+    fn test_epoch() {
+        let mut notes = Vec::new();
+        let mut pkg = package("2023.1", Some("2023.1"));
+        pkg.epoch = Some(1);
+        assert!(pkg.parse_version("1.0", &mut notes));
+        assert_eq!(pkg.version, "1:1.0");
+        assert!(!pkg.is_latest());
+        pkg.local_version = Some("1:1.0".into());
+        assert!(pkg.is_latest());
+        assert!(!pkg.parse_tag("v1.0", &mut notes));
+    }
+
+    #[tokio::test]
+    async fn test_add_spec() {
+        let spec = PackageSpec {
+            distname: "tool".into(),
+            master_site: "https://example.com/".into(),
+            version: "1.2.0".into(),
+            local_version: Some("1.1.0".into()),
+            source: None,
+            stable_only: true,
+            held: false,
+            pattern: None,
+            json_path: None,
+            scan_text: false,
+            mirrors: vec!["https://mirror.example.com/".into()],
+            all_releases: false,
+            selector: Some("#stable a".into()),
+            descend: false,
+            semver: false,
+            ignore: None,
+            pinned: None,
+            tag_prefix: None,
+            tag_suffix: None,
+            epoch: Some(1),
+        };
+        let draft = Package::draft(&spec, "html", Some(60));
+        let pool = crate::db::memory().await;
+        let mut conn = pool.acquire().await.unwrap();
+        Package::add_spec(&mut conn, spec, "html", Some(60))
+            .await
+            .unwrap();
+        drop(conn);
+        let pkg = Package::fetch_by_name(&pool, "tool").await.unwrap();
+        assert_eq!(
+            toml::to_string(&pkg.spec()).unwrap(),
+            toml::to_string(&draft.spec()).unwrap()
+        );
+        assert_eq!(pkg.check_interval, Some(60));
+    }
+
+    #[tokio::test]
+    async fn test_history() {
+        let pool = crate::db::memory().await;
+        let mut pkg = Package::add(
+            &pool,
+            "sudo".into(),
+            "https://www.sudo.ws/dist/".into(),
+            "1.9.13".into(),
+            None,
+            "html",
+        )
+        .await
+        .unwrap();
+        assert!(pkg.history(&pool).await.unwrap().is_empty());
+
+        pkg.version = "1.9.14".into();
+        pkg.store_version(&pool, "1.9.13").await.unwrap();
+        pkg.version = "1.9.15".into();
+        pkg.store_version(&pool, "1.9.14").await.unwrap();
+        // unchanged version is not recorded
+        pkg.store_version(&pool, "1.9.15").await.unwrap();
+
+        let history = pkg.history(&pool).await.unwrap();
+        let changes: Vec<_> = history
+            .iter()
+            .map(|change| (change.old_version.as_str(), change.new_version.as_str()))
+            .collect();
+        assert_eq!(changes, [("1.9.13", "1.9.14"), ("1.9.14", "1.9.15")]);
+    }
+
+    #[tokio::test]
+    async fn test_rename() {
+        let pool = crate::db::memory().await;
+        for distname in ["sudo", "doas"] {
+            Package::add(
+                &pool,
+                distname.into(),
+                "https://www.sudo.ws/dist/".into(),
+                "1.9.13".into(),
+                None,
+                "html",
+            )
+            .await
+            .unwrap();
+        }
+        let mut pkg = Package::fetch_by_name(&pool, "sudo").await.unwrap();
+
+        let err = pkg.rename(&pool, "doas".into()).await.unwrap_err();
+        assert!(err
+            .as_database_error()
+            .is_some_and(|err| err.is_unique_violation()));
+        assert_eq!(pkg.distname, "sudo");
+
+        pkg.rename(&pool, "sudo-rs".into()).await.unwrap();
+        let renamed = Package::fetch_by_name(&pool, "sudo-rs").await.unwrap();
+        assert_eq!(renamed.id, pkg.id);
+        assert!(Package::fetch_by_name(&pool, "sudo").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_source() {
+        let pool = crate::db::memory().await;
+        let mut pkg = Package::add(
+            &pool,
+            "project".into(),
+            "https://github.com/owner/project/releases".into(),
+            "1.0".into(),
+            None,
+            "github",
+        )
+        .await
+        .unwrap();
+        pkg.etag = Some("\"abc\"".into());
+
+        // release page scraped instead of asking the API
+        pkg.update(&pool, None, None, None, None, Some("html"))
+            .await
+            .unwrap();
+        assert!(pkg.etag.is_none());
+        let pkg = Package::fetch_by_name(&pool, "project").await.unwrap();
+        assert_eq!(pkg.source, "html");
+        assert_eq!(pkg.master_site, "https://github.com/owner/project/releases");
+    }
+
+    #[tokio::test]
+    async fn test_find() {
+        let pool = crate::db::memory().await;
+        for distname in ["sudo", "sudo-rs", "libsodium", "ldns"] {
+            Package::add(
+                &pool,
+                distname.into(),
+                "https://example.com/".into(),
+                "1.0".into(),
+                None,
+                "html",
+            )
+            .await
+            .unwrap();
+        }
+        let find = |name: &'static str, exact| {
+            let pool = pool.clone();
+            async move {
+                Package::find(&pool, name, exact)
+                    .await
+                    .map(|pkg| pkg.distname)
+            }
+        };
+
+        assert_eq!(find("sudo", false).await.unwrap(), "sudo");
+        assert_eq!(find("sudo-", false).await.unwrap(), "sudo-rs");
+        // prefix match wins over substring in libsodium
+        assert!(matches!(
+            find("sud", false).await,
+            Err(FindError::Ambiguous(_, names)) if names == ["sudo", "sudo-rs"]
+        ));
+        assert_eq!(find("dns", false).await.unwrap(), "ldns");
+        assert!(matches!(
+            find("dns", true).await,
+            Err(FindError::NotFound(_))
+        ));
+        assert!(matches!(
+            find("nginx", false).await,
+            Err(FindError::NotFound(_))
+        ));
+    }
+
+    /// Answer one HTTP request at `/dist` with `response`. Returns URL, and handle yielding
+    /// the request in lowercase.
+    fn serve_once(response: &'static [u8]) -> (String, std::thread::JoinHandle<String>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/dist", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let len = stream.read(&mut request).unwrap();
+            stream.write_all(response).unwrap();
+            String::from_utf8_lossy(&request[..len]).to_lowercase()
+        });
+        (url, server)
+    }
+
+    /// Check package `tool` of `source`, known at `version`, with master site serving
+    /// `response` once; `setup` changes package before. Returns pool, package, check result
+    /// and the request served.
+    async fn check_served(
+        response: &'static [u8],
+        version: &str,
+        source: &str,
+        setup: impl FnOnce(&mut Package),
+    ) -> (SqlitePool, Package, CheckResult, String) {
+        let (master_site, server) = serve_once(response);
+        let pool = crate::db::memory().await;
+        let mut pkg = Package::add(
+            &pool,
+            "tool".into(),
+            master_site,
+            version.into(),
+            None,
+            source,
+        )
+        .await
+        .unwrap();
+        setup(&mut pkg);
+
+        let config = Config::default();
+        let client = http_client(&config.http).unwrap();
+        let result = pkg
+            .check(&pool, &client, None, &config, false)
+            .await
+            .unwrap();
+        let request = server.join().unwrap();
+        (pool, pkg, result, request)
+    }
+
+    const PAGE_1_9_14: &[u8] =
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n\
+        <a href=\"tool-1.9.14.tar.gz\">tool-1.9.14.tar.gz</a>";
+
+    #[tokio::test]
+    async fn test_permanent_location() {
+        let (target, target_server) = serve_once(b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n");
+        let redirect = format!(
+            "HTTP/1.1 301 Moved Permanently\r\nLocation: {target}\r\nConnection: close\r\n\r\n"
+        );
+        let (origin, origin_server) =
+            serve_once(Box::leak(redirect.into_bytes().into_boxed_slice()));
+        let config = Config::default();
+        let client = redirect_client(&config.http).unwrap();
+        let location = permanent_location(&client, &format!("{origin}#longterm"), &config.http)
+            .await
+            .unwrap();
+        origin_server.join().unwrap();
+        target_server.join().unwrap();
+        assert_eq!(location, Some(format!("{target}#longterm")));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_version() {
+        let (_, _, result, _) = check_served(PAGE_1_9_14, "unknown", "html", |_| ()).await;
+        assert!(result.error.is_none());
+        assert!(result.updated);
+        assert_eq!(result.version, "1.9.14");
+    }
+
+    #[tokio::test]
+    async fn test_json_source() {
+        let (_, _, result, _) = check_served(
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n\
+            {\"info\": {\"version\": \"v2.1.0\"}}",
+            "2.0.0",
+            "json",
+            |pkg| pkg.json_path = Some("info.version".into()),
+        )
+        .await;
+        assert!(result.error.is_none());
+        assert!(result.updated);
+        assert_eq!(result.version, "2.1.0");
+    }
+
+    #[tokio::test]
+    async fn test_selector() {
+        let (_, _, result, _) = check_served(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n\
+            <div id=\"beta\"><a href=\"tool-3.0.0b1.tar.gz\">beta</a></div>\
+            <div id=\"stable\"><a href=\"tool-2.1.0.tar.gz\">stable</a></div>",
+            "2.0.0",
+            "html",
+            |pkg| pkg.selector = Some("#stable a".into()),
+        )
+        .await;
+        assert!(result.error.is_none());
+        assert_eq!(result.version, "2.1.0");
+    }
+
+    #[tokio::test]
+    async fn test_ignore() {
+        let (_, _, result, _) = check_served(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n\
+            <a href=\"tool-2.1.0.tar.gz\">broken</a>\
+            <a href=\"tool-2.0.5.tar.gz\">fixed</a>",
+            "2.0.0",
+            "html",
+            |pkg| pkg.ignore = Some(r"^2\.1\.0$".into()),
+        )
+        .await;
+        assert!(result.error.is_none());
+        assert_eq!(result.version, "2.0.5");
+    }
+
+    #[tokio::test]
+    async fn test_ignore_invalid_version() {
+        let (_, _, result, _) = check_served(PAGE_1_9_14, "unknown", "html", |pkg| {
+            pkg.ignore = Some(r"^1\.9\.14$".into());
+        })
+        .await;
+        assert!(!result.updated);
+        assert_eq!(result.version, "unknown");
+        assert_eq!(result.notes, ["No version for tool"]);
+    }
+
+    #[tokio::test]
+    async fn test_pinned() {
+        let (pool, mut pkg, result, _) = check_served(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n\
+            <a href=\"tool-3.0.0.tar.gz\">tool-3.0.0.tar.gz</a>",
+            "2.4.0",
+            "html",
+            |pkg| pkg.pinned = Some("2.4.0".into()),
+        )
+        .await;
+        assert!(result.updated);
+        assert_eq!(result.version, "3.0.0");
+        assert_eq!(
+            result.notes,
+            ["tool is pinned at 2.4.0, upstream version is 3.0.0"]
+        );
+        assert!(!pkg.is_outdated());
+        // pin was set for the check only; store it as `pin` command does
+        pkg.set_pinned(&pool, Some("2.4.0".into())).await.unwrap();
+        assert_eq!(Package::total(&pool).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_total() {
+        let pool = crate::db::memory().await;
+        for (distname, version, local_version) in [
+            ("past-pin", "3.0.0", Some("2.5.0")),
+            ("zeros", "1.2.0", Some("1.2")),
+            ("outdated", "2.0", Some("1.0")),
+            ("unknown", "1.0", None),
+        ] {
+            let pkg = Package::add(
+                &pool,
+                distname.into(),
+                "https://example.com/".into(),
+                version.into(),
+                None,
+                "html",
+            )
+            .await
+            .unwrap();
+            query!(
+                "UPDATE package SET local_version = $2 WHERE id = $1",
+                pkg.id,
+                local_version
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        Package::fetch_by_name(&pool, "past-pin")
+            .await
+            .unwrap()
+            .set_pinned(&pool, Some("2.4.0".into()))
+            .await
+            .unwrap();
+        let outdated: Vec<Package> = Package::outdated_stream(&pool).collect().await;
+        assert_eq!(outdated.len(), 2);
+        assert_eq!(Package::total(&pool).await.unwrap(), outdated.len());
+    }
+
+    #[tokio::test]
+    async fn test_not_modified() {
+        let mut last_check = None;
+        let (pool, _, result, request) = check_served(
+            b"HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n",
+            "1.9.13",
+            "html",
+            |pkg| {
+                pkg.etag = Some("\"abc\"".into());
+                last_check = Some(pkg.last_check);
+            },
+        )
+        .await;
+        assert!(request.contains("if-none-match: \"abc\""));
+        assert!(!result.updated);
+        assert!(result.error.is_none());
+
+        let pkg = Package::fetch_by_name(&pool, "tool").await.unwrap();
+        assert_eq!(pkg.version, "1.9.13");
+        assert!(Some(pkg.last_check) > last_check);
+        assert_eq!(pkg.etag.as_deref(), Some("\"abc\""));
+    }
+
+    #[tokio::test]
+    async fn test_mirrors() {
+        let (mirror, mirror_server) = serve_once(PAGE_1_9_14);
+        let (_, pkg, result, _) = check_served(
+            b"HTTP/1.1 404 Not Found\r\nConnection: close\r\n\r\n",
+            "1.9.13",
+            "html",
+            |pkg| pkg.mirrors.clone_from(&mirror),
+        )
+        .await;
+        mirror_server.join().unwrap();
+        assert!(result.updated);
+        assert!(result.error.is_none());
+        assert_eq!(
+            result.notes,
+            [format!("Version of tool found at mirror {mirror}")]
+        );
+        assert_eq!(pkg.version, "1.9.14");
+    }
+
+    #[tokio::test]
+    async fn test_mirrors_invalid_version() {
+        let (pool, _, result, _) = check_served(PAGE_1_9_14, "unknown", "html", |pkg| {
+            // never asked, as master site has a version
+            pkg.mirrors = "http://127.0.0.1:9/dist".into();
+        })
+        .await;
+        assert!(result.updated);
+        assert_eq!(
+            result.notes,
+            ["Invalid version unknown of tool, replaced with 1.9.14"]
+        );
+        let pkg = Package::fetch_by_name(&pool, "tool").await.unwrap();
+        assert_eq!(pkg.version, "1.9.14");
+    }
+
+    /// Backend failing at master site, and finding a tag at any other site.
+    struct MirroredBackend;
+
+    impl Backend for MirroredBackend {
+        fn latest<'a>(
+            &'a self,
+            query: &'a mut Query<'_>,
+        ) -> BoxFuture<'a, Result<Found, CheckError>> {
+            Box::pin(async move {
+                if query.site == "https://example.com/" {
+                    Err(CheckError::Status(StatusCode::SERVICE_UNAVAILABLE))
+                } else {
+                    Ok(Found::Tag("v1.1".into()))
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_check_with() {
+        let mut pkg = package("1.0", None);
+        pkg.mirrors = "https://mirror.example.com/".into();
+        let config = Config::default();
+        let client = http_client(&config.http).unwrap();
+        let mut notes = Vec::new();
+        let updated = pkg
+            .auto_check_with(&MirroredBackend, &client, &config, &mut notes)
+            .await
+            .unwrap();
+        assert!(updated);
+        assert_eq!(pkg.version, "1.1");
+        assert_eq!(
+            notes,
+            ["Version of test found at mirror https://mirror.example.com/"]
+        );
+        // the same version again
+        notes.clear();
+        let updated = pkg
+            .auto_check_with(&MirroredBackend, &client, &config, &mut notes)
+            .await
+            .unwrap();
+        assert!(!updated);
+        assert!(notes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unmark() {
+        let pool = crate::db::memory().await;
+        let mut pkg = Package::add(
+            &pool,
+            "sudo".into(),
+            "https://www.sudo.ws/dist/".into(),
+            "1.9.13".into(),
+            None,
+            "html",
+        )
+        .await
+        .unwrap();
+        assert_eq!(pkg.unmark(&pool).await.unwrap(), UnmarkOutcome::NotMarked);
+
+        pkg.version = "1.9.14".into();
+        assert_eq!(
+            pkg.mark_latest(&pool).await.unwrap(),
+            MarkOutcome::Marked(Some("1.9.13".into()))
+        );
+        assert_eq!(
+            pkg.mark_latest(&pool).await.unwrap(),
+            MarkOutcome::AlreadyLatest
+        );
+        pkg.version = "1.9.15".into();
+        pkg.mark_latest(&pool).await.unwrap();
+        assert_eq!(
+            pkg.unmark(&pool).await.unwrap(),
+            UnmarkOutcome::Reverted("1.9.15".into(), Some("1.9.14".into()))
+        );
+        assert_eq!(pkg.local_version.as_deref(), Some("1.9.14"));
+        pkg.local_version = Some("1.9.16".into());
+        assert_eq!(
+            pkg.unmark(&pool).await.unwrap(),
+            UnmarkOutcome::Changed("1.9.14".into())
+        );
+        pkg.local_version = Some("1.9.14".into());
+        assert!(matches!(
+            pkg.unmark(&pool).await.unwrap(),
+            UnmarkOutcome::Reverted(_, _)
+        ));
+        assert_eq!(pkg.unmark(&pool).await.unwrap(), UnmarkOutcome::NotMarked);
+
+        let pkg = Package::fetch_by_name(&pool, "sudo").await.unwrap();
+        assert_eq!(pkg.local_version.as_deref(), Some("1.9.13"));
+    }
+
+    #[tokio::test]
+    async fn test_search() {
+        let pool = crate::db::memory().await;
+        for (distname, master_site) in [
+            ("sudo", "https://www.sudo.ws/dist/"),
+            (
+                "sudo_rs",
+                "https://github.com/trifectatechfoundation/sudo-rs",
+            ),
+            ("ripgrep", "https://github.com/BurntSushi/ripgrep"),
+        ] {
+            Package::add(
+                &pool,
+                distname.into(),
+                master_site.into(),
+                "1.0".into(),
+                None,
+                "html",
+            )
+            .await
+            .unwrap();
+        }
+        let search = |pattern: &'static str, in_master_site| {
+            let pool = pool.clone();
+            async move {
+                Package::search(&pool, pattern, in_master_site)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|pkg| pkg.distname)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(search("SUD", false).await, ["sudo", "sudo_rs"]);
+        assert_eq!(search("o_", false).await, ["sudo_rs"]);
+        assert!(search("github", false).await.is_empty());
+        assert_eq!(search("github", true).await, ["ripgrep", "sudo_rs"]);
+    }
+
+    #[tokio::test]
+    async fn test_store_error() {
+        let pool = crate::db::memory().await;
+        let mut pkg = Package::add(
+            &pool,
+            "sudo".into(),
+            "https://www.sudo.ws/dist/".into(),
+            "1.9.13".into(),
+            None,
+            "html",
+        )
+        .await
+        .unwrap();
+
+        pkg.store_error(&pool, Some("status 404 Not Found".into()))
+            .await
+            .unwrap();
+        let failed = Package::failed(&pool).await.unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(
+            failed[0].last_error.as_deref(),
+            Some("status 404 Not Found")
+        );
+        assert!(failed[0].last_error_at.is_some());
+
+        pkg.update_last_check(&pool).await.unwrap();
+        assert!(Package::failed(&pool).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut pkgs = vec![
+            package("1.2", Some("1.1")),
+            package("1.2", Some("1.2")),
+            package("2.0", Some("1.0")),
+            package("3.0", None),
+        ];
+        pkgs[2].held = true;
+        pkgs[3].source = "github".into();
+        assert_eq!(
+            Package::stats(&pkgs),
+            [
+                SourceStats {
+                    source: "github".into(),
+                    total: 1,
+                    outdated: 1,
+                },
+                SourceStats {
+                    source: "html".into(),
+                    total: 3,
+                    outdated: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_summary() {
+        let result = |distname: &str, updated, error: Option<&str>| CheckResult {
+            distname: distname.into(),
+            master_site: "https://example.com/".into(),
+            local_version: None,
+            old_version: "1.0".into(),
+            version: if updated { "1.1" } else { "1.0" }.into(),
+            updated,
+            error: error.map(Into::into),
+            notes: Vec::new(),
+        };
+        let mut summary = CheckSummary::default();
+        summary.add(&result("a", true, None));
+        summary.add(&result("b", false, None));
+        summary.add(&result("c", false, Some("status 404 Not Found")));
+        summary.add(&result("d", false, Some("invalid URL")));
+        assert_eq!(summary.to_string(), "Checked 4, updated 1, failed 2: c, d");
+        assert_eq!(
+            CheckSummary::default().to_string(),
+            "Checked 0, updated 0, failed 0"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use crate::backend::{self, Backend};
+use reqwest::Url;
+use std::{fmt, str::FromStr};
+
+/// Where to look for new versions of a package.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Pypi,
+    Crates,
+    Npm,
+    RubyGems,
+    Hackage,
+    MetaCpan,
+    DockerHub,
+    Packagist,
+    Pecl,
+    Maven,
+    NuGet,
+    Gnu,
+    Arch,
+    FDroid,
+    Kernel,
+    GitHub,
+    GitLab,
+    Gitea,
+    Bitbucket,
+    Git,
+    Sourcehut,
+    SourceForge,
+    Feed,
+    Ftp,
+    Redirect,
+    Html,
+    Json,
+}
+
+impl Source {
+    /// Names accepted by `FromStr`.
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        backend::names()
+    }
+
+    /// Guess source from master site URL. Unparseable URL is treated as HTML page.
+    #[must_use]
+    pub fn detect(master_site: &str) -> Self {
+        Url::parse(master_site)
+            .ok()
+            .and_then(|url| backend::detect(&url))
+            .unwrap_or(Self::Html)
+    }
+
+    /// Backend looking for versions from this source.
+    #[must_use]
+    pub fn backend(self) -> &'static dyn Backend {
+        backend::backend(self)
+    }
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        backend::name(self)
+    }
+}
+
+impl FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        backend::named(s).ok_or_else(|| format!("unknown source {s}"))
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let detect = Source::detect;
+        assert_eq!(detect("https://pypi.org/project/requests"), Source::Pypi);
+        assert_eq!(detect("https://pypi.python.org/pypi/six/"), Source::Pypi);
+        assert_eq!(detect("https://crates.io/crates/serde"), Source::Crates);
+        assert_eq!(
+            detect("https://www.npmjs.com/package/typescript"),
+            Source::Npm
+        );
+        assert_eq!(detect("https://rubygems.org/gems/rails"), Source::RubyGems);
+        assert_eq!(
+            detect("https://hackage.haskell.org/package/pandoc"),
+            Source::Hackage
+        );
+        assert_eq!(detect("https://metacpan.org/pod/Moose"), Source::MetaCpan);
+        assert_eq!(detect("https://hub.docker.com/_/nginx"), Source::DockerHub);
+        assert_eq!(
+            detect("https://packagist.org/packages/monolog/monolog"),
+            Source::Packagist
+        );
+        assert_eq!(detect("https://pecl.php.net/package/redis"), Source::Pecl);
+        assert_eq!(
+            detect("https://central.sonatype.com/artifact/org.slf4j/slf4j-api"),
+            Source::Maven
+        );
+        assert_eq!(
+            detect("https://www.nuget.org/packages/Newtonsoft.Json"),
+            Source::NuGet
+        );
+        assert_eq!(detect("https://ftp.gnu.org/gnu/bash/"), Source::Gnu);
+        assert_eq!(detect("https://www.kernel.org/#longterm"), Source::Kernel);
+        assert_eq!(
+            detect("https://aur.archlinux.org/packages/yay"),
+            Source::Arch
+        );
+        assert_eq!(
+            detect("https://f-droid.org/packages/org.fdroid.fdroid/"),
+            Source::FDroid
+        );
+        assert_eq!(detect("https://github.com/rust-lang/rust"), Source::GitHub);
+        assert_eq!(
+            detect("https://bitbucket.org/multicoreware/x265_git/downloads/"),
+            Source::Bitbucket
+        );
+        assert_eq!(
+            detect("git+https://github.com/rust-lang/rust.git"),
+            Source::Git
+        );
+        assert_eq!(
+            detect("https://git.sr.ht/~sircmpwn/scdoc/refs"),
+            Source::Sourcehut
+        );
+        assert_eq!(
+            detect("https://gitlab.com/gitlab-org/gitlab"),
+            Source::GitLab
+        );
+        assert_eq!(
+            detect("https://codeberg.org/forgejo/forgejo"),
+            Source::Gitea
+        );
+        assert_eq!(
+            detect("https://sourceforge.net/projects/zsh/"),
+            Source::SourceForge
+        );
+        assert_eq!(
+            detect("https://github.com/rust-lang/rust/releases.atom"),
+            Source::Feed
+        );
+        assert_eq!(detect("https://blog.example.com/news.rss"), Source::Feed);
+        assert_eq!(detect("ftp://ftp.openssl.org/source/"), Source::Ftp);
+        assert_eq!(
+            detect("https://www.example.com/download/latest/"),
+            Source::Redirect
+        );
+        assert_eq!(
+            detect("https://github.com/owner/repo/releases/latest"),
+            Source::GitHub
+        );
+        assert_eq!(detect("https://www.sudo.ws/dist/"), Source::Html);
+        assert_eq!(detect("http://127.0.0.1/dist/"), Source::Html);
+        assert_eq!(detect("not a URL"), Source::Html);
+    }
+
+    #[test]
+    fn test_names() {
+        for name in Source::names() {
+            let source = Source::from_str(name).unwrap();
+            assert_eq!(source.as_str(), name);
+            // serde names follow the same table
+            assert_eq!(
+                serde_json::to_string(&source).unwrap(),
+                format!("\"{name}\"")
+            );
+        }
+        assert!(Source::from_str("gopher").is_err());
+    }
+}
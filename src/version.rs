@@ -1,72 +1,607 @@
+use crate::error::ParseVersionError;
 use html5ever::{
     local_name,
     tendril::StrTendril,
     tokenizer::{
         BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
     },
-    Attribute, QualName,
+    Attribute,
 };
-use std::{cmp::Ordering, fmt, str::FromStr};
+use regex::Regex;
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+/// Stage of a pre-release, from the earliest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Tier {
+    Dev,
+    Alpha,
+    Beta,
+    /// Release candidate; `pre` and `preview` too.
+    Rc,
+}
+
+impl Tier {
+    fn from_marker(marker: &str) -> Option<Self> {
+        match marker {
+            "dev" => Some(Self::Dev),
+            "a" | "alpha" => Some(Self::Alpha),
+            "b" | "beta" => Some(Self::Beta),
+            "pre" | "preview" | "rc" => Some(Self::Rc),
+            _ => None,
+        }
+    }
+}
 
-#[derive(Debug)]
+/// Pre-release marker, as `rc1` in `1.2.0rc1`, `beta` in `1.2.0-beta` or `alpha.2` in
+/// `2.0.0-alpha.2`. Ordered by tier, then by number; no number comes first.
+#[derive(Clone, Debug)]
+struct Prerelease {
+    tier: Tier,
+    number: Option<i32>,
+    /// Marker as written, lowercase.
+    label: String,
+}
+
+impl PartialEq for Prerelease {
+    fn eq(&self, other: &Self) -> bool {
+        (self.tier, self.number) == (other.tier, other.number)
+    }
+}
+
+impl Eq for Prerelease {}
+
+impl Ord for Prerelease {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.tier, self.number).cmp(&(other.tier, other.number))
+    }
+}
+
+impl Hash for Prerelease {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.tier, self.number).hash(state);
+    }
+}
+
+impl PartialOrd for Prerelease {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Release version: numeric components with optional epoch, letter, patch level,
+/// post-release and pre-release marker. Ordered by epoch, components, letter, patch level and
+/// post-release number, with pre-release before the release itself.
+#[derive(Clone, Debug)]
 pub struct Version {
+    /// Epoch, as in `1:2.3.4` or `1!2.3.4`, overriding the components; 0 means no epoch.
+    epoch: i32,
     v: Vec<i32>,
+    /// Letter after the last component, as in `1.1.1w`, ordered after no letter.
+    letter: Option<char>,
+    /// Patch level, as in `1.8.10p1`; 0 means no patch level.
+    patch: i32,
+    /// Post-release, as in `1.2.3.post1`; `post` without number is 0.
+    post: Option<i32>,
+    pre: Option<Prerelease>,
 }
 
 impl Version {
     #[must_use]
     pub fn new(v: Vec<i32>) -> Self {
-        Self { v }
+        Self {
+            epoch: 0,
+            v,
+            letter: None,
+            patch: 0,
+            post: None,
+            pre: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_patch(v: Vec<i32>, patch: i32) -> Self {
+        Self {
+            patch,
+            ..Self::new(v)
+        }
+    }
+
+    /// Same version in `epoch`, unless it has its own.
+    #[must_use]
+    pub fn with_epoch(mut self, epoch: i32) -> Self {
+        if self.epoch == 0 {
+            self.epoch = epoch;
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+
+    /// Looks like calendar version: starts with a year, as in `2023.10.1`.
+    #[must_use]
+    pub fn is_calver(&self) -> bool {
+        self.v
+            .first()
+            .is_some_and(|first| (1990..=2999).contains(first))
+    }
+
+    /// Numeric components without trailing zeros, so `1.2` and `1.2.0` compare equal.
+    fn components(&self) -> &[i32] {
+        let len = self.v.iter().rposition(|d| *d != 0).map_or(0, |i| i + 1);
+        &self.v[..len]
     }
 }
 
 impl PartialEq for Version {
     fn eq(&self, other: &Self) -> bool {
-        self.v == *other.v
+        self.epoch == other.epoch
+            && self.components() == other.components()
+            && self.letter == other.letter
+            && self.patch == other.patch
+            && self.post == other.post
+            && self.pre == other.pre
     }
 }
 
 impl PartialEq<Vec<i32>> for Version {
     fn eq(&self, other: &Vec<i32>) -> bool {
-        self.v == *other
+        *self == Self::new(other.clone())
+    }
+}
+
+impl Eq for Version {}
+
+impl Hash for Version {
+    // consistent with `eq`, so trailing zeros are not hashed
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.epoch.hash(state);
+        self.components().hash(state);
+        self.letter.hash(state);
+        self.patch.hash(state);
+        self.post.hash(state);
+        self.pre.hash(state);
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| self.components().cmp(other.components()))
+            .then(self.letter.cmp(&other.letter))
+            .then(self.patch.cmp(&other.patch))
+            // no post-release comes first
+            .then(self.post.cmp(&other.post))
+            // pre-release comes before release
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
     }
 }
 
 impl PartialOrd for Version {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.v.partial_cmp(&other.v)
+        Some(self.cmp(other))
     }
 }
 
 impl PartialOrd<Vec<i32>> for Version {
     fn partial_cmp(&self, other: &Vec<i32>) -> Option<Ordering> {
-        self.v.partial_cmp(other)
+        self.partial_cmp(&Self::new(other.clone()))
+    }
+}
+
+/// Split a segment like `10p1` into numeric base and patch level.
+fn split_patch(segment: &str) -> Option<(i32, i32)> {
+    let (base, patch) = segment.split_once('p')?;
+    Some((i32::from_str(base).ok()?, i32::from_str(patch).ok()?))
+}
+
+/// Split a segment like `1w` into numeric base and a single lowercase letter, as OpenSSL
+/// marks its bug fix releases.
+fn split_letter(segment: &str) -> Option<(i32, char)> {
+    let mut chars = segment.chars();
+    let letter = chars.next_back().filter(char::is_ascii_lowercase)?;
+    Some((i32::from_str(chars.as_str()).ok()?, letter))
+}
+
+/// Split a segment like `post1`, or `3post1` glued to the last component, into numeric base,
+/// if any, and post-release number.
+fn split_post(segment: &str) -> Option<(Option<i32>, i32)> {
+    let (base, number) = segment.split_once("post")?;
+    let base = if base.is_empty() {
+        None
+    } else {
+        Some(i32::from_str(base).ok()?)
+    };
+    let number = if number.is_empty() {
+        0
+    } else {
+        i32::from_str(number).ok()?
+    };
+    Some((base, number))
+}
+
+/// Split date written without separators, like `20240501`, or with time of day, like
+/// `202405011230` or `20240501123000`, into components, so it is ordered along with
+/// calendar versions like `2024.5.1`. Numbers which are no valid date stay single numbers.
+fn split_date(segment: &str) -> Option<Vec<i32>> {
+    if !matches!(segment.len(), 8 | 12 | 14) || !segment.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // year, month, day, hour, minute, second: where they end, and their valid values
+    let fields = [
+        (4, 1990..=2999),
+        (6, 1..=12),
+        (8, 1..=31),
+        (10, 0..=23),
+        (12, 0..=59),
+        (14, 0..=59),
+    ];
+    let mut start = 0;
+    let mut date = Vec::new();
+    for (end, valid) in fields
+        .into_iter()
+        .take_while(|(end, _)| *end <= segment.len())
+    {
+        let field = i32::from_str(&segment[start..end]).ok()?;
+        if !valid.contains(&field) {
+            return None;
+        }
+        date.push(field);
+        start = end;
+    }
+    Some(date)
+}
+
+/// Split epoch from the start of version, as in `1:2.3.4` or `1!2.3.4`. Version after `:`
+/// needs at least two components, so time of day like `10:30` is not taken for one.
+fn split_epoch(s: &str) -> Option<(i32, &str)> {
+    let (epoch, rest) = s.split_once([':', '!'])?;
+    let major = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let separator = s.as_bytes()[epoch.len()];
+    if major == 0 || (separator == b':' && !rest[major..].starts_with('.')) {
+        return None;
+    }
+    Some((i32::from_str(epoch).ok()?, rest))
+}
+
+/// Pre-release markers, as in `1.2.0-beta`, `1.2.0rc1` or `2.0.0.dev3`.
+const PRERELEASE_MARKERS: [&str; 6] = ["alpha", "beta", "dev", "pre", "preview", "rc"];
+/// PyPI short pre-release markers, recognized only when numbered, as in `1.2.0a1`.
+const SHORT_PRERELEASE_MARKERS: [&str; 2] = ["a", "b"];
+
+/// Split a segment like `0rc1` into numeric base, if any, and pre-release marker.
+fn split_prerelease(segment: &str) -> Option<(Option<i32>, Prerelease)> {
+    let marker_start = segment
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(segment.len());
+    let base = i32::from_str(&segment[..marker_start]).ok();
+    let rest = &segment[marker_start..];
+    let marker_end = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    let (marker, number) = rest.split_at(marker_end);
+    let marker = marker.to_ascii_lowercase();
+    let numbered = !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit());
+    let prerelease = if numbered || number.is_empty() {
+        PRERELEASE_MARKERS.contains(&marker.as_str())
+            || (numbered && SHORT_PRERELEASE_MARKERS.contains(&marker.as_str()))
+    } else {
+        false
+    };
+    if !prerelease {
+        return None;
+    }
+    let pre = Prerelease {
+        tier: Tier::from_marker(&marker)?,
+        number: i32::from_str(number).ok(),
+        label: rest.to_ascii_lowercase(),
+    };
+    Some((base, pre))
+}
+
+/// Criteria for versions found upstream to be considered.
+#[derive(Default)]
+pub struct VersionFilter {
+    /// Ignore pre-release versions.
+    pub stable_only: bool,
+    /// Currently known version. Versions found must follow the same scheme: calendar or not,
+    /// and single number only if it is one or a year. Without it, single numbers are ignored.
+    pub current: Option<Version>,
+    /// Versions matching this regular expression are skipped, like `-beta` or a broken
+    /// release.
+    pub ignore: Option<Regex>,
+    /// Epoch of versions found which have none. Versions of another epoch than `current`
+    /// follow a new scheme, so they are not held to its scheme.
+    pub epoch: i32,
+}
+
+impl VersionFilter {
+    #[must_use]
+    pub fn accepts(&self, version: &Version) -> bool {
+        if self.stable_only && version.is_prerelease() {
+            return false;
+        }
+        if self
+            .ignore
+            .as_ref()
+            .is_some_and(|ignore| ignore.is_match(&version.to_string()))
+        {
+            return false;
+        }
+        let epoch = if version.epoch == 0 {
+            self.epoch
+        } else {
+            version.epoch
+        };
+        match &self.current {
+            Some(current) if current.epoch == epoch => {
+                current.is_calver() == version.is_calver()
+                    && (current.v.len() == 1 || version.v.len() > 1 || version.is_calver())
+            }
+            _ => version.v.len() > 1,
+        }
     }
 }
 
 impl FromStr for Version {
-    type Err = ();
+    type Err = ParseVersionError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some(index) = s.find(|c: char| c.is_ascii_digit()) {
-            // if let Some(index) = s.find(|c: char| c == '-' || c == '_') {
-            let v: Vec<i32> = s[index..]
-                .split(&['.', '-'])
-                .map_while(|d| i32::from_str(d).ok())
-                .collect();
-            if v.len() > 1 {
-                Ok(Self { v })
+        let Some(index) = s.find(|c: char| c.is_ascii_digit()) else {
+            return Err(ParseVersionError::NoNumber);
+        };
+        // single number must stand alone, not be part of a word like `SHA256`
+        let glued = s[..index]
+            .strip_suffix(['v', 'V'])
+            .unwrap_or(&s[..index])
+            .ends_with(|c: char| c.is_ascii_alphanumeric());
+        let (epoch, rest) = split_epoch(&s[index..]).unwrap_or((0, &s[index..]));
+        let mut v = Vec::new();
+        let mut letter = None;
+        let mut patch = 0;
+        let mut post = None;
+        let mut pre = None;
+        let mut segments = rest.split(&['.', '-']);
+        while let Some(segment) = segments.next() {
+            if v.is_empty() && !glued {
+                if let Some(date) = split_date(segment) {
+                    v = date;
+                    continue;
+                }
+            }
+            if let Ok(d) = i32::from_str(segment) {
+                v.push(d);
+                continue;
+            }
+            // letter, patch level, post-release or pre-release marker ends the version
+            if let Some((base, number)) = split_post(&segment.to_ascii_lowercase()) {
+                v.extend(base);
+                post = Some(number);
+            } else if let Some((d, p)) = split_patch(segment) {
+                v.push(d);
+                patch = p;
+            } else if let Some((base, mut marker)) = split_prerelease(segment) {
+                v.extend(base);
+                // number in its own segment, as in `2.0.0-rc.1`
+                if marker.number.is_none() {
+                    if let Some(number) = segments.next().and_then(|n| i32::from_str(n).ok()) {
+                        marker.number = Some(number);
+                        marker.label = format!("{}.{number}", marker.label);
+                    }
+                }
+                pre = Some(marker);
+            } else if let Some((d, l)) = split_letter(segment) {
+                v.push(d);
+                letter = Some(l);
+            }
+            break;
+        }
+        match v.len() {
+            0 => Err(ParseVersionError::Malformed),
+            1 if glued => Err(ParseVersionError::Glued),
+            _ => Ok(Self {
+                epoch,
+                v,
+                letter,
+                patch,
+                post,
+                pre,
+            }),
+        }
+    }
+}
+
+/// Pre-release identifier of semantic version.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Identifier {
+    /// Compared numerically, and before alphanumeric ones.
+    Numeric(u64),
+    /// Compared lexically in ASCII order.
+    Alphanumeric(String),
+}
+
+/// Semantic version, ordered as in <https://semver.org>: pre-release identifiers are
+/// compared one by one, a longer list of equal identifiers is higher, and release is higher
+/// than any of its pre-releases. Build metadata, as in `1.0.0+20240101`, is ignored.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SemVer {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Vec<Identifier>,
+}
+
+impl FromStr for SemVer {
+    type Err = ParseVersionError;
+
+    /// Parse version like `1.0.0-rc.1+build.5`, with optional `v` prefix. Missing minor or
+    /// patch number is 0.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix(['v', 'V']).unwrap_or(s);
+        let s = s.split_once('+').map_or(s, |(version, _build)| version);
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (s, None),
+        };
+        let mut numbers = core.split('.').map(|number| {
+            if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) {
+                u64::from_str(number).map_err(|_| ParseVersionError::Malformed)
             } else {
-                Err(())
+                Err(ParseVersionError::Malformed)
             }
-        } else {
-            Err(())
+        });
+        let major = numbers.next().ok_or(ParseVersionError::Malformed)??;
+        let minor = numbers.next().transpose()?.unwrap_or_default();
+        let patch = numbers.next().transpose()?.unwrap_or_default();
+        if numbers.next().is_some() {
+            return Err(ParseVersionError::Malformed);
+        }
+        let pre = match pre {
+            None => Vec::new(),
+            Some(pre) => pre
+                .split('.')
+                .map(|identifier| {
+                    if identifier.is_empty()
+                        || !identifier
+                            .bytes()
+                            .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+                    {
+                        Err(ParseVersionError::Malformed)
+                    } else if identifier.bytes().all(|b| b.is_ascii_digit()) {
+                        u64::from_str(identifier)
+                            .map(Identifier::Numeric)
+                            .map_err(|_| ParseVersionError::Malformed)
+                    } else {
+                        Ok(Identifier::Alphanumeric(identifier.into()))
+                    }
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+}
+
+impl Ord for SemVer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Version part of a release tag, like `1.7.1` of `jq-1.7.1`. It starts at the first number
+/// not glued to letters other than a leading `v`, so `python3-bindings-1.2` yields `1.2`.
+/// Dates with dashes or underscores, like `release-2024-05`, become `2024.05`.
+#[must_use]
+pub fn tag_release(tag: &str) -> String {
+    let bytes = tag.as_bytes();
+    let boundary = |i: usize| i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+    let start = (0..bytes.len())
+        .filter(|&i| bytes[i].is_ascii_digit())
+        .find(|&i| boundary(i) || (matches!(bytes[i - 1], b'v' | b'V') && boundary(i - 1)))
+        .or_else(|| bytes.iter().position(u8::is_ascii_digit));
+    let Some(start) = start else {
+        return tag.to_string();
+    };
+    let release = &tag[start..];
+    dashed_date(release).unwrap_or_else(|| release.to_string())
+}
+
+/// Date at the start of release, with dashes or underscores, like `2024-05-01` or `2024_05`,
+/// written with dots instead. Whatever follows the date is kept.
+fn dashed_date(release: &str) -> Option<String> {
+    let separator = *release.as_bytes().get(4)?;
+    if !matches!(separator, b'-' | b'_') {
+        return None;
+    }
+    let year = &release[..4];
+    if !year.bytes().all(|b| b.is_ascii_digit())
+        || !(1990..=2999).contains(&i32::from_str(year).ok()?)
+    {
+        return None;
+    }
+    // month, then day, each of one or two digits
+    let field = |s: &str, valid: std::ops::RangeInclusive<i32>| {
+        let len = s.bytes().take_while(u8::is_ascii_digit).count();
+        let value = i32::from_str(s.get(..len)?).ok()?;
+        (matches!(len, 1 | 2) && valid.contains(&value)).then_some(len)
+    };
+    let mut date = year.to_string();
+    let mut rest = &release[5..];
+    let len = field(rest, 1..=12)?;
+    date.push('.');
+    date.push_str(&rest[..len]);
+    rest = &rest[len..];
+    if rest.as_bytes().first() == Some(&separator) {
+        if let Some(len) = field(&rest[1..], 1..=31) {
+            date.push('.');
+            date.push_str(&rest[1..=len]);
+            rest = &rest[len + 1..];
         }
     }
+    date.push_str(rest);
+    Some(date)
+}
+
+/// Release found upstream in `epoch`, unless it has its own: `1.0` in epoch 1 becomes
+/// `1:1.0`, or `1!7` for single numbers, which would look like time of day.
+#[must_use]
+pub fn epoch_release(release: &str, epoch: i32) -> String {
+    if epoch == 0 || !Version::from_str(release).is_ok_and(|version| version.epoch == 0) {
+        return release.to_string();
+    }
+    let index = release.find(|c: char| c.is_ascii_digit()).unwrap_or(0);
+    let (prefix, rest) = release.split_at(index);
+    let with_colon = format!("{prefix}{epoch}:{rest}");
+    if Version::from_str(&with_colon).is_ok_and(|version| version.epoch == epoch) {
+        with_colon
+    } else {
+        format!("{prefix}{epoch}!{rest}")
+    }
+}
+
+/// Parse version given by user, which has to be comparable with versions found upstream.
+pub fn parse_release(release: &str) -> Result<Version, String> {
+    Version::from_str(release)
+        .map_err(|err| format!("invalid version {release} ({err}), expected numbers like 1.2.3"))
 }
 
 impl fmt::Display for Version {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}:", self.epoch)?;
+        }
         let mut first = true;
         for digit in &self.v {
             if first {
@@ -76,77 +611,423 @@ impl fmt::Display for Version {
             }
             write!(f, "{digit}")?;
         }
+        if let Some(letter) = self.letter {
+            write!(f, "{letter}")?;
+        }
+        if self.patch != 0 {
+            write!(f, "p{}", self.patch)?;
+        }
+        if let Some(post) = self.post {
+            write!(f, ".post{post}")?;
+        }
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre.label)?;
+        }
         Ok(())
     }
 }
 
-struct VersionSink {
+/// Links to files which are not releases: checksums, signatures and the like.
+pub const IGNORED_SUFFIXES: [&str; 14] = [
+    ".asc",
+    ".md5",
+    ".minisig",
+    ".sha1",
+    ".sha256",
+    ".sha512",
+    ".sig",
+    ".sign",
+    ".txt",
+    "MD5SUMS",
+    "SHA1SUMS",
+    "SHA256",
+    "SHA256SUMS",
+    "SHA512",
+];
+
+/// Check if linked file name ends with one of `suffixes`, or starts with one followed by a
+/// separator, as in `SHA256-2.0`. Case, query and fragment are ignored.
+pub(crate) fn is_ignored(href: &str, suffixes: &[String]) -> bool {
+    let path = href.split(&['?', '#']).next().unwrap_or_default();
+    let name = path
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    suffixes.iter().any(|suffix| {
+        let suffix = suffix.to_ascii_lowercase();
+        name.ends_with(&suffix)
+            || name
+                .strip_prefix(&suffix)
+                .is_some_and(|rest| rest.starts_with(|c: char| !c.is_ascii_alphanumeric()))
+    })
+}
+
+struct VersionSink<'a> {
     version: Option<Version>,
+    filter: &'a VersionFilter,
+    /// Ignore links with these suffixes.
+    ignored_suffixes: &'a [String],
+    /// Look for versions in text of elements, values of options and `data-version`
+    /// attributes too, not only in links.
+    scan_text: bool,
+    /// Text since the last tag.
+    text: String,
+    /// Inside `script` or `style` element, whose text is not shown.
+    hidden: bool,
 }
 
-impl VersionSink {
-    pub fn new() -> Self {
-        Self { version: None }
+impl<'a> VersionSink<'a> {
+    pub fn new(filter: &'a VersionFilter, ignored_suffixes: &'a [String], scan_text: bool) -> Self {
+        Self {
+            version: None,
+            filter,
+            ignored_suffixes,
+            scan_text,
+            text: String::new(),
+            hidden: false,
+        }
     }
+
+    /// Keep version found in link or text, if it is the highest so far.
+    fn offer(&mut self, candidate: &str) {
+        if is_ignored(candidate, self.ignored_suffixes) {
+            return;
+        }
+        if let Ok(version) = Version::from_str(candidate) {
+            if !self.filter.accepts(&version) {
+                return;
+            }
+            match &self.version {
+                None => self.version = Some(version),
+                Some(v) => {
+                    if v < &version {
+                        self.version = Some(version);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Look for version in text collected since the last tag.
+    fn flush_text(&mut self) {
+        let text = std::mem::take(&mut self.text);
+        let text = text.trim();
+        if !text.is_empty() {
+            self.offer(text);
+        }
+    }
+}
+
+/// Value of attribute `name` of a tag.
+fn attribute<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|attr| &*attr.name.local == name)
+        .map(|attr| &*attr.value)
 }
 
-impl TokenSink for VersionSink {
+impl TokenSink for VersionSink<'_> {
     type Handle = ();
 
     // string_cache::Atom<LocalNameStaticSet>
     fn process_token(&mut self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
-        if let Token::TagToken(Tag {
-            kind: TagKind::StartTag,
-            name: local_name!("a"),
-            attrs,
-            ..
-        }) = token
-        {
-            for attr in &attrs {
-                if let Attribute {
-                    name:
-                        QualName {
-                            local: local_name!("href"),
-                            ..
-                        },
-                    value,
-                } = attr
-                {
-                    if let Ok(version) = Version::from_str(value.as_ref()) {
-                        match &self.version {
-                            None => self.version = Some(version),
-                            Some(v) => {
-                                if v < &version {
-                                    self.version = Some(version);
-                                }
-                            }
+        match token {
+            Token::TagToken(Tag {
+                kind, name, attrs, ..
+            }) => {
+                self.flush_text();
+                if kind == TagKind::StartTag && self.scan_text {
+                    if let Some(value) = attribute(&attrs, "data-version") {
+                        self.offer(value);
+                    }
+                }
+                match name {
+                    local_name!("a") if kind == TagKind::StartTag => {
+                        if let Some(href) = attribute(&attrs, "href") {
+                            self.offer(href);
+                        }
+                    }
+                    local_name!("option") if kind == TagKind::StartTag && self.scan_text => {
+                        if let Some(value) = attribute(&attrs, "value") {
+                            self.offer(value);
                         }
                     }
+                    local_name!("script") | local_name!("style") => {
+                        self.hidden = kind == TagKind::StartTag;
+                    }
+                    _ => (),
                 }
             }
+            Token::CharacterTokens(text) if self.scan_text && !self.hidden => {
+                self.text.push_str(&text);
+            }
+            Token::EOFToken => self.flush_text(),
+            _ => (),
         }
         TokenSinkResult::Continue
     }
 }
 
+/// Find the highest version linked from HTML page, or with `scan_text` also in text of its
+/// elements, values of options and `data-version` attributes, accepted by `filter`. Links
+/// ending with one of `ignored_suffixes` are skipped.
 #[must_use]
-pub fn parse_html(html: &str) -> Option<Version> {
+pub fn parse_html(
+    html: &str,
+    filter: &VersionFilter,
+    ignored_suffixes: &[String],
+    scan_text: bool,
+) -> Option<Version> {
     let mut chunk = StrTendril::new();
     chunk.push_slice(html);
     let mut input = BufferQueue::new();
     input.push_back(chunk.try_reinterpret().unwrap());
 
-    let mut tok = Tokenizer::new(VersionSink::new(), TokenizerOpts::default());
+    let mut tok = Tokenizer::new(
+        VersionSink::new(filter, ignored_suffixes, scan_text),
+        TokenizerOpts::default(),
+    );
     let _ = tok.feed(&mut input);
     tok.end();
 
     tok.sink.version
 }
 
+/// Elements without content, which have no end tag.
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// CSS selector of page parts to look at for versions. Type, class and ID selectors are
+/// supported, like `div.downloads` or `#stable`, and space between them meaning descendant,
+/// like `main table.files`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Selector(Vec<Compound>);
+
+/// Conditions on a single element, like `div.downloads`.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Compound {
+    name: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+/// Element open at the current position in HTML page.
+struct Element {
+    name: String,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+impl Element {
+    fn new(tag: &Tag) -> Self {
+        let attr = |name: &str| {
+            tag.attrs
+                .iter()
+                .find(|attr| &*attr.name.local == name)
+                .map(|attr| attr.value.to_string())
+        };
+        Self {
+            name: tag.name.to_string(),
+            id: attr("id"),
+            classes: attr("class")
+                .map(|classes| classes.split_whitespace().map(Into::into).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Compound {
+    fn matches(&self, element: &Element) -> bool {
+        self.name.as_ref().is_none_or(|name| *name == element.name)
+            && (self.id.is_none() || self.id == element.id)
+            && self
+                .classes
+                .iter()
+                .all(|class| element.classes.contains(class))
+    }
+}
+
+impl Selector {
+    /// Whether the innermost element of `stack`, or one of its ancestors, is selected.
+    fn matches(&self, stack: &[Element]) -> bool {
+        let mut compounds = self.0.iter().peekable();
+        for element in stack {
+            if compounds
+                .peek()
+                .is_some_and(|compound| compound.matches(element))
+            {
+                compounds.next();
+            }
+        }
+        compounds.peek().is_none()
+    }
+}
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valid = |name: &str| {
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        };
+        let mut compounds = Vec::new();
+        for part in s.split_whitespace() {
+            let mut compound = Compound::default();
+            // element name, then `.class` and `#id` in any order
+            let starts: Vec<usize> = part.match_indices(['.', '#']).map(|(i, _)| i).collect();
+            let name = &part[..starts.first().copied().unwrap_or(part.len())];
+            if !name.is_empty() && name != "*" {
+                if !valid(name) {
+                    return Err(format!("unsupported selector {s}"));
+                }
+                compound.name = Some(name.to_ascii_lowercase());
+            }
+            for (index, &start) in starts.iter().enumerate() {
+                let end = starts.get(index + 1).copied().unwrap_or(part.len());
+                let value = &part[start + 1..end];
+                if !valid(value) {
+                    return Err(format!("unsupported selector {s}"));
+                }
+                if part[start..].starts_with('#') {
+                    compound.id = Some(value.into());
+                } else {
+                    compound.classes.push(value.into());
+                }
+            }
+            compounds.push(compound);
+        }
+        if compounds.is_empty() {
+            return Err("empty selector".into());
+        }
+        Ok(Self(compounds))
+    }
+}
+
+/// Collects HTML of selected elements.
+struct SelectSink<'a> {
+    selector: &'a Selector,
+    /// Elements open at the current position.
+    stack: Vec<Element>,
+    html: String,
+}
+
+impl SelectSink<'_> {
+    fn push_tag(&mut self, tag: &Tag) {
+        self.html.push('<');
+        if tag.kind == TagKind::EndTag {
+            self.html.push('/');
+        }
+        self.html.push_str(&tag.name);
+        for attr in &tag.attrs {
+            let value = attr.value.replace('&', "&amp;").replace('"', "&quot;");
+            self.html
+                .push_str(&format!(" {}=\"{value}\"", &*attr.name.local));
+        }
+        self.html.push('>');
+    }
+}
+
+impl TokenSink for SelectSink<'_> {
+    type Handle = ();
+
+    fn process_token(&mut self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        match token {
+            Token::TagToken(tag) if tag.kind == TagKind::StartTag => {
+                self.stack.push(Element::new(&tag));
+                if self.selector.matches(&self.stack) {
+                    self.push_tag(&tag);
+                }
+                if tag.self_closing || VOID_ELEMENTS.contains(&&*tag.name) {
+                    self.stack.pop();
+                }
+            }
+            Token::TagToken(tag) => {
+                if self.selector.matches(&self.stack) {
+                    self.push_tag(&tag);
+                }
+                // unclosed elements inside end too
+                if let Some(index) = self
+                    .stack
+                    .iter()
+                    .rposition(|element| *element.name == *tag.name)
+                {
+                    self.stack.truncate(index);
+                }
+            }
+            Token::CharacterTokens(text) if self.selector.matches(&self.stack) => {
+                self.html
+                    .push_str(&text.replace('&', "&amp;").replace('<', "&lt;"));
+            }
+            _ => (),
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+/// HTML of elements matching `selector`, with their content, so only these parts of page
+/// are looked at.
+#[must_use]
+pub fn select_html(html: &str, selector: &Selector) -> String {
+    let mut chunk = StrTendril::new();
+    chunk.push_slice(html);
+    let mut input = BufferQueue::new();
+    input.push_back(chunk.try_reinterpret().unwrap());
+
+    let mut tok = Tokenizer::new(
+        SelectSink {
+            selector,
+            stack: Vec::new(),
+            html: String::new(),
+        },
+        TokenizerOpts::default(),
+    );
+    let _ = tok.feed(&mut input);
+    tok.end();
+
+    tok.sink.html
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_release() {
+        assert_eq!(parse_release("1.2.3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(parse_release("v2.0").unwrap(), vec![2, 0]);
+        assert!(parse_release("latest").is_err());
+        assert!(parse_release("").is_err());
+    }
+
+    #[test]
+    fn test_tag_release() {
+        assert_eq!(tag_release("v1.2.3"), "1.2.3");
+        assert_eq!(tag_release("release-1.2"), "1.2");
+        assert_eq!(tag_release("jq-1.7.1"), "1.7.1");
+        assert_eq!(tag_release("python3-bindings-1.2"), "1.2");
+        assert_eq!(tag_release("r1234"), "1234");
+        assert_eq!(tag_release("nightly"), "nightly");
+        assert_eq!(tag_release("release-2024-05"), "2024.05");
+        assert_eq!(tag_release("2024_05_17"), "2024.05.17");
+        assert_eq!(tag_release("snapshot-2024-05-17-rc1"), "2024.05.17-rc1");
+        // no valid date
+        assert_eq!(tag_release("release-2024-13"), "2024-13");
+        assert_eq!(tag_release("1.2-3"), "1.2-3");
+        let v = |s| Version::from_str(&tag_release(s)).unwrap();
+        assert!(v("release-2024-05-17") > v("release-2024-05-02"));
+        assert!(v("release-2024-10") > v("release-2024-9"));
+    }
+
+    fn ignored_suffixes() -> Vec<String> {
+        IGNORED_SUFFIXES.iter().map(ToString::to_string).collect()
+    }
+
     #[test]
     fn test_version() {
         assert!(Version::from_str("package.tar.gz").is_err());
@@ -159,10 +1040,247 @@ mod tests {
         assert_eq!(Version::from_str("package-1.2.3").unwrap(), v);
         assert_eq!(Version::from_str("package-1.2-3").unwrap(), v);
         assert_eq!(Version::from_str("package-1.2.3.tar.gz").unwrap(), v);
-        assert_eq!(Version::from_str("package-1.2.3.post1").unwrap(), v);
         // assert_eq!(Version::from_str("xyz3-1.2.3").unwrap(), v);
     }
 
+    #[test]
+    fn test_version_error() {
+        assert_eq!(
+            Version::from_str("package.tar.gz"),
+            Err(ParseVersionError::NoNumber)
+        );
+        assert_eq!(Version::from_str("SHA256"), Err(ParseVersionError::Glued));
+        assert_eq!(
+            Version::from_str("10:30"),
+            Err(ParseVersionError::Malformed)
+        );
+        assert_eq!(
+            SemVer::from_str("1.2.3.4"),
+            Err(ParseVersionError::Malformed)
+        );
+        assert_eq!(
+            parse_release("SHA256").unwrap_err(),
+            "invalid version SHA256 (number is part of a word), expected numbers like 1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_version_hash() {
+        use std::collections::HashSet;
+
+        let versions: HashSet<Version> = ["1.2", "1.2.0", "v1.2.0.0", "1.2.0rc1", "1.2.0-rc.1"]
+            .iter()
+            .map(|s| Version::from_str(s).unwrap())
+            .collect();
+        assert_eq!(versions.len(), 2);
+        let versions: HashSet<SemVer> = ["1.0.0+build.1", "1.0.0", "1.0.0-rc.1"]
+            .iter()
+            .map(|s| SemVer::from_str(s).unwrap())
+            .collect();
+        assert_eq!(versions.len(), 2);
+    }
+
+    #[test]
+    fn test_version_calver() {
+        let v = |s| Version::from_str(s).unwrap();
+        assert!(v("2023.10.1") > v("2023.9.9"));
+        assert!(v("2023.10.1").is_calver());
+        assert!(v("23.09") < v("23.10"));
+        assert!(!v("1.2.3").is_calver());
+
+        assert_eq!(v("2024"), vec![2024]);
+        assert_eq!(v("release-2024.tar.gz"), vec![2024]);
+        assert_eq!(v("v2024"), vec![2024]);
+        assert!(v("2024").is_calver());
+        assert!(v("20231015").is_calver());
+        assert!(v("2024") > v("2023.12.1"));
+        assert_eq!(v("less-643.tar.gz"), vec![643]);
+        assert!(Version::from_str("md5").is_err());
+        assert!(Version::from_str("page2").is_err());
+    }
+
+    #[test]
+    fn test_version_date() {
+        let v = |s| Version::from_str(s).unwrap();
+        assert_eq!(v("20240501"), vec![2024, 5, 1]);
+        assert_eq!(v("snapshot-20240501.tar.gz"), v("2024.05.01"));
+        assert_eq!(v("20240501"), v("2024.5.1"));
+        assert!(v("20240501").is_calver());
+        assert!(v("20240502") > v("2024.5.1"));
+        assert!(v("20240501") < v("2024.5.1.1"));
+        assert!(v("20240501.1") > v("20240501"));
+        assert_eq!(v("202405011230"), vec![2024, 5, 1, 12, 30]);
+        assert!(v("20240501123001") > v("202405011230"));
+        assert!(v("nightly-202405011230") > v("20240501"));
+        // no valid date, so a plain number
+        assert_eq!(v("20241301"), vec![20241301]);
+        assert_eq!(v("12345678"), vec![12345678]);
+        assert!(!v("20241301").is_calver());
+        // too big for a number, and no date either
+        assert!(Version::from_str("202405019999").is_err());
+        // part of a word
+        assert!(Version::from_str("build20240501").is_err());
+    }
+
+    #[test]
+    fn test_version_filter() {
+        let v = |s| Version::from_str(s).unwrap();
+        let any = VersionFilter::default();
+        assert!(any.accepts(&v("1.2.3")));
+        assert!(!any.accepts(&v("2024")));
+
+        let semver = VersionFilter {
+            current: Some(v("1.2.3")),
+            ..Default::default()
+        };
+        assert!(semver.accepts(&v("1.3.0")));
+        assert!(!semver.accepts(&v("2019.1")));
+        assert!(!semver.accepts(&v("5")));
+
+        let calver = VersionFilter {
+            stable_only: true,
+            current: Some(v("2023.10.1")),
+            ..Default::default()
+        };
+        assert!(calver.accepts(&v("2024.1.0")));
+        assert!(calver.accepts(&v("2024")));
+        assert!(!calver.accepts(&v("3.0.0")));
+        assert!(!calver.accepts(&v("2024.1.0rc1")));
+
+        let ignore = VersionFilter {
+            ignore: Regex::new(r"-beta|^1\.4\.0$").ok(),
+            ..Default::default()
+        };
+        assert!(ignore.accepts(&v("1.3.0")));
+        assert!(!ignore.accepts(&v("1.4.0")));
+        assert!(ignore.accepts(&v("1.4.0rc1")));
+        assert!(!ignore.accepts(&v("1.5.0-beta.1")));
+
+        let single = VersionFilter {
+            current: Some(v("643")),
+            ..Default::default()
+        };
+        assert!(single.accepts(&v("661")));
+        assert!(single.accepts(&v("661.1")));
+    }
+
+    #[test]
+    fn test_version_patch() {
+        let v = Version::from_str("sudo-1.8.10p1.tar.gz").unwrap();
+        assert_eq!(v, Version::with_patch(vec![1, 8, 10], 1));
+        assert_eq!(&v.to_string(), "1.8.10p1");
+
+        let p2 = Version::from_str("1.8.10p2").unwrap();
+        let p1 = Version::from_str("1.8.10p1").unwrap();
+        let bare = Version::from_str("1.8.10").unwrap();
+        assert!(p2 > p1);
+        assert!(p1 > bare);
+        assert!(bare < p1);
+        assert_ne!(p1, bare);
+        assert!(Version::from_str("1.8.11").unwrap() > p2);
+
+        // OpenSSH portable releases
+        let v = |s| Version::from_str(s).unwrap();
+        assert!(v("openssh-9.3p2.tar.gz") > v("openssh-9.3p1.tar.gz"));
+        assert!(v("9.3p1") > v("9.3"));
+        assert!(v("9.4p1") > v("9.3p2"));
+        assert_eq!(&v("openssh-9.3p2.tar.gz").to_string(), "9.3p2");
+    }
+
+    #[test]
+    fn test_version_letter() {
+        let v = |s| Version::from_str(s).unwrap();
+        let chain = [
+            "1.1.0l", "1.1.1", "1.1.1a", "1.1.1v", "1.1.1w", "1.1.2", "3.0.0",
+        ];
+        for pair in chain.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(&v("openssl-1.1.1w.tar.gz").to_string(), "1.1.1w");
+        assert_ne!(v("1.1.1w"), v("1.1.1"));
+        assert!(!v("1.1.1b").is_prerelease());
+        // numbered short marker is still a pre-release
+        assert!(v("1.2.0b1").is_prerelease());
+        assert!(v("1.2.0b1") < v("1.2.0"));
+    }
+
+    #[test]
+    fn test_version_post() {
+        let v = |s| Version::from_str(s).unwrap();
+        assert!(v("package-1.2.3.post1") > v("1.2.3"));
+        assert!(v("1.2.3.post2") > v("1.2.3.post1"));
+        assert!(v("1.2.3.post1") > v("1.2.3.post"));
+        assert!(v("1.2.3.post") > v("1.2.3"));
+        assert!(v("1.2.4") > v("1.2.3.post1"));
+        assert_eq!(v("1.2.3post1"), v("1.2.3-post1"));
+        assert_eq!(&v("pkg-1.2.3.post1.tar.gz").to_string(), "1.2.3.post1");
+        assert_eq!(&v("1.2.3.post").to_string(), "1.2.3.post0");
+    }
+
+    #[test]
+    fn test_version_epoch() {
+        let v = |s| Version::from_str(s).unwrap();
+        assert!(v("1:1.0") > v("2.0"));
+        assert!(v("2!1.0") > v("1:3.0"));
+        assert_eq!(v("1:1.0"), v("1!1.0.0"));
+        assert_ne!(v("1:1.0"), v("1.0"));
+        assert_eq!(&v("1:2.3.4").to_string(), "1:2.3.4");
+        assert_eq!(&v("2.3.4").to_string(), "2.3.4");
+        // time of day is no version with epoch
+        assert!(Version::from_str("10:30").is_err());
+        assert!(v("1!7") > v("2023.1"));
+    }
+
+    #[test]
+    fn test_epoch_release() {
+        assert_eq!(epoch_release("1.0", 0), "1.0");
+        assert_eq!(epoch_release("1.0", 1), "1:1.0");
+        assert_eq!(epoch_release("v1.0", 1), "v1:1.0");
+        assert_eq!(epoch_release("7", 1), "1!7");
+        assert_eq!(epoch_release("2:1.0", 1), "2:1.0");
+        assert_eq!(epoch_release("latest", 1), "latest");
+        let v = |s: &str| Version::from_str(s).unwrap();
+        assert!(v(&epoch_release("1.0", 1)) > v("2023.1"));
+        assert_eq!(v(&epoch_release("1.0", 1)), v("1.0").with_epoch(1));
+        // scheme of known version held in the same epoch only
+        let filter = VersionFilter {
+            current: Some(v("2023.1")),
+            epoch: 1,
+            ..Default::default()
+        };
+        assert!(filter.accepts(&v("1.0")));
+        let filter = VersionFilter {
+            current: Some(v("1:1.0")),
+            ..filter
+        };
+        assert!(filter.accepts(&v("1.1")));
+        assert!(!filter.accepts(&v("2024.1")));
+    }
+
+    #[test]
+    fn test_version_trailing_zeros() {
+        let v = |s| Version::from_str(s).unwrap();
+        assert_eq!(v("1.2"), v("1.2.0"));
+        assert_eq!(v("1.2.0"), v("1.2.0.0"));
+        assert_eq!(v("1.2"), vec![1, 2, 0]);
+        assert!(v("1.2") < v("1.2.1"));
+        assert!(v("1.2.0") < v("1.2.1"));
+        assert!(v("1.3") > v("1.2.9"));
+        assert!(v("1.2.0") <= v("1.2"));
+        assert_ne!(v("1.0.2"), v("1.2"));
+    }
+
+    #[test]
+    fn test_version_sort() {
+        let mut versions: Vec<Version> = ["1.10.0", "1.9.0", "1.2.0"]
+            .iter()
+            .map(|s| Version::from_str(s).unwrap())
+            .collect();
+        versions.sort();
+        let sorted: Vec<String> = versions.iter().map(ToString::to_string).collect();
+        assert_eq!(sorted, ["1.2.0", "1.9.0", "1.10.0"]);
+    }
+
     #[test]
     fn test_version_string() {
         let version = Version::new(vec![1, 2, 3]);
@@ -189,9 +1307,234 @@ mod tests {
 <a href="sudo-1.8.10p1.tar.gz">sudo-1.8.10p1.tar.gz</a>                               13-Mar-2014 21:20             2260994
 <a href="sudo-1.8.10p1.tar.gz.sig">sudo-1.8.10p1.tar.gz.sig</a>                           04-Dec-2017 22:45                 543
 <a href="sudo-1.8.10p2.patch.gz">sudo-1.8.10p2.patch.gz</a>                             17-Mar-2014 14:33                2692
+<a href="sudo-1.8.11.tar.gz.sig">sudo-1.8.11.tar.gz.sig</a>                             04-Dec-2017 22:45                 543
+<a href="sudo-1.8.11.tar.gz.asc?download=1">sudo-1.8.11.tar.gz.asc</a>                             04-Dec-2017 22:45                 543
+<a href="SHA256-2.0">SHA256-2.0</a>                                         12-Jun-2022 20:57               13753
+<a href="ChangeLog-1.9.0.txt">ChangeLog-1.9.0.txt</a>                                12-Jun-2022 20:57               13753
 </body></html>
 "#;
-        let v = parse_html(html);
-        assert_eq!(v, Some(Version::new(vec![1, 8, 10])));
+        let v = parse_html(html, &VersionFilter::default(), &ignored_suffixes(), false);
+        assert_eq!(v, Some(Version::with_patch(vec![1, 8, 10], 2)));
+        // without skip list, stray files win
+        let v = parse_html(html, &VersionFilter::default(), &[], false);
+        assert_eq!(v, Some(Version::new(vec![256, 2, 0])));
+    }
+
+    #[test]
+    fn test_parse_html_text() {
+        let html = r#"<html><head><script>var version = "9.9.9";</script></head>
+<body><h1>sudo</h1>
+<p>Latest stable release: <span>sudo 1.9.15</span></p>
+<a href="/download">sudo 1.9.14p3</a>
+<a href="/dist/">Older releases</a>
+<a href="sudo-1.9.13.tar.gz">download</a>
+</body></html>"#;
+        let filter = VersionFilter::default();
+        assert_eq!(
+            parse_html(html, &filter, &[], true),
+            Some(Version::new(vec![1, 9, 15]))
+        );
+        // only links by default
+        assert_eq!(
+            parse_html(html, &filter, &[], false),
+            Some(Version::new(vec![1, 9, 13]))
+        );
+
+        // versions hidden behind opaque download links
+        let html = r#"<a href="https://cdn.example.com/f/8c1e0b7a">app 3.2.1</a>
+<select><option value="3.4.0">latest</option><option value="3.3.0">3.3.0</option></select>
+<button data-version="3.5.0" data-href="/get/1f2e">Download</button>"#;
+        assert_eq!(
+            parse_html(html, &filter, &[], true),
+            Some(Version::new(vec![3, 5, 0]))
+        );
+        let html = r#"<a href="/get/8c1e0b7a">app 3.2.1</a>
+<select><option value="3.4.0">latest</option></select>"#;
+        assert_eq!(
+            parse_html(html, &filter, &[], true),
+            Some(Version::new(vec![3, 4, 0]))
+        );
+        assert_eq!(parse_html(html, &filter, &[], false), None);
+    }
+
+    #[test]
+    fn test_selector() {
+        assert_eq!(
+            Selector::from_str("div.downloads #stable a").unwrap(),
+            Selector(vec![
+                Compound {
+                    name: Some("div".into()),
+                    id: None,
+                    classes: vec!["downloads".into()],
+                },
+                Compound {
+                    name: None,
+                    id: Some("stable".into()),
+                    classes: Vec::new(),
+                },
+                Compound {
+                    name: Some("a".into()),
+                    id: None,
+                    classes: Vec::new(),
+                },
+            ])
+        );
+        assert!(Selector::from_str("ul > li").is_err());
+        assert!(Selector::from_str("a[href]").is_err());
+        assert!(Selector::from_str("div.").is_err());
+        assert!(Selector::from_str(" ").is_err());
+    }
+
+    #[test]
+    fn test_select_html() {
+        let html = r#"<html><body>
+<div class="release beta"><a href="tool-2.0-beta1.tar.gz">beta</a></div>
+<div id="stable" class="release"><p>Stable<br>release</p>
+<a href="tool-1.4.2.tar.gz">tool &amp; docs</a><img src="logo.png"></div>
+<a href="tool-3.0.tar.gz">development snapshot</a>
+</body></html>"#;
+        let select = |selector| select_html(html, &Selector::from_str(selector).unwrap());
+        assert_eq!(
+            select("#stable a"),
+            r#"<a href="tool-1.4.2.tar.gz">tool &amp; docs</a>"#
+        );
+        assert_eq!(
+            select("div.release.beta"),
+            r#"<div class="release beta"><a href="tool-2.0-beta1.tar.gz">beta</a></div>"#
+        );
+        let stable = select("div#stable");
+        assert!(stable.starts_with(r#"<div id="stable" class="release"><p>Stable<br>release</p>"#));
+        assert!(stable.ends_with(r#"<img src="logo.png"></div>"#));
+        assert_eq!(
+            parse_html(&stable, &VersionFilter::default(), &[], false),
+            Some(Version::new(vec![1, 4, 2]))
+        );
+        assert_eq!(select("table"), "");
+    }
+
+    #[test]
+    fn test_is_ignored() {
+        let suffixes = ignored_suffixes();
+        assert!(is_ignored("sudo-1.8.10p1.tar.gz.sig", &suffixes));
+        assert!(is_ignored("sudo-1.8.10p1.tar.gz.SIG", &suffixes));
+        assert!(is_ignored("/dist/SHA256", &suffixes));
+        assert!(is_ignored("pkg-1.0.tar.gz.sha256#top", &suffixes));
+        assert!(is_ignored("SHA256-2.0", &suffixes));
+        assert!(!is_ignored("sha256sum-1.0.tar.gz", &suffixes));
+        assert!(!is_ignored("sudo-1.8.10p1.tar.gz", &suffixes));
+        assert!(!is_ignored("sudo-1.8.10p1.tar.gz", &[]));
+    }
+
+    #[test]
+    fn test_version_prerelease() {
+        let v = |s| Version::from_str(s).unwrap();
+        assert!(v("1.2.0rc1").is_prerelease());
+        assert!(v("1.2.0-beta").is_prerelease());
+        assert!(v("2.0.0.dev3").is_prerelease());
+        assert!(v("1.2.0a1").is_prerelease());
+        assert!(v("package-2.0.0-rc.1.tar.gz").is_prerelease());
+        assert!(!v("1.2.0").is_prerelease());
+        assert!(!v("package-1.2.0.tar.gz").is_prerelease());
+        assert!(!v("1.2.3.post1").is_prerelease());
+        assert!(!v("1.8.10p1").is_prerelease());
+        assert!(v("1.2.0rc1") < v("1.2.0"));
+        assert!(v("1.2.0rc1") > v("1.1.9"));
+        assert_eq!(&v("pkg-1.3rc1.tar.gz").to_string(), "1.3-rc1");
+        assert_eq!(&v("pkg-2.0.0-rc.1.tar.gz").to_string(), "2.0.0-rc.1");
+    }
+
+    #[test]
+    fn test_semver() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        // https://semver.org/#spec-item-11
+        let chain = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.1.0",
+            "2.0.0",
+        ];
+        for pair in chain.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(v("1.0.0-rc.1+build.5"), v("1.0.0-rc.1"));
+        assert_eq!(v("v1.2.3+20240101"), v("1.2.3"));
+        assert_eq!(v("1.2"), v("1.2.0"));
+        assert!(v("1.0.0-rc.1") < v("1.0.0-rc.1.1"));
+        assert!(v("1.0.0-x-y.1") > v("1.0.0-x.1"));
+        for invalid in [
+            "",
+            "latest",
+            "1.2.3.4",
+            "1..2",
+            "1.0.0-",
+            "1.0.0-rc..1",
+            "1.0.0-rc_1",
+        ] {
+            assert!(SemVer::from_str(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_version_prerelease_order() {
+        let v = |s| Version::from_str(s).unwrap();
+        let chain = [
+            "1.9.9",
+            "2.0.0.dev1",
+            "2.0.0-alpha",
+            "2.0.0-alpha.1",
+            "2.0.0-alpha.2",
+            "2.0.0-alpha.10",
+            "2.0.0b1",
+            "2.0.0-beta.2",
+            "2.0.0rc1",
+            "2.0.0-rc.2",
+            "2.0.0",
+            "2.0.1a1",
+        ];
+        for pair in chain.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        // same tier and number, however written
+        assert_eq!(v("2.0.0a1"), v("2.0.0-alpha.1"));
+        assert_eq!(v("2.0.0rc1"), v("2.0.0-rc.1"));
+        assert_eq!(v("2.0.0-preview.3"), v("2.0.0rc3"));
+
+        let mut versions: Vec<Version> = ["2.0.0", "2.0.0-rc.1", "2.0.0-beta.1", "2.0.0-alpha.1"]
+            .iter()
+            .map(|s| v(s))
+            .collect();
+        versions.sort();
+        let sorted: Vec<String> = versions.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            sorted,
+            ["2.0.0-alpha.1", "2.0.0-beta.1", "2.0.0-rc.1", "2.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_parse_html_stable_only() {
+        let html = r#"<a href="pkg-1.2.0.tar.gz">pkg-1.2.0.tar.gz</a>
+<a href="pkg-1.3.0rc1.tar.gz">pkg-1.3.0rc1.tar.gz</a>
+<a href="pkg-1.3.0-beta.tar.gz">pkg-1.3.0-beta.tar.gz</a>
+<a href="pkg-2.0.0.dev3.tar.gz">pkg-2.0.0.dev3.tar.gz</a>"#;
+        let stable_only = VersionFilter {
+            stable_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_html(html, &stable_only, &[], false),
+            Some(Version::new(vec![1, 2, 0]))
+        );
+        assert_eq!(
+            parse_html(html, &VersionFilter::default(), &[], false),
+            Some(Version::from_str("2.0.0.dev3").unwrap())
+        );
     }
 }